@@ -0,0 +1,163 @@
+//! Typed values for the FLIR Lepton AGC (automatic gain control) module (`0x0100`).
+//!
+//! See Lepton Software IDD Rev 303, AGC module.
+
+use crate::lepton_cci::{CciWords, DecodeError};
+
+/// AGC algorithm selection (`0x0104`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgcPolicy {
+    Linear = 0,
+    /// Histogram equalization.
+    Heq = 1,
+}
+
+impl TryFrom<u32> for AgcPolicy {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AgcPolicy::Linear),
+            1 => Ok(AgcPolicy::Heq),
+            _ => Err(DecodeError::new("AgcPolicy", value)),
+        }
+    }
+}
+
+impl From<AgcPolicy> for u32 {
+    fn from(value: AgcPolicy) -> Self {
+        value as u32
+    }
+}
+
+/// Output range of the HEQ stage (`0x0144`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgcHeqScale {
+    EightBits = 0,
+    FourteenBits = 1,
+}
+
+impl TryFrom<u32> for AgcHeqScale {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AgcHeqScale::EightBits),
+            1 => Ok(AgcHeqScale::FourteenBits),
+            _ => Err(DecodeError::new("AgcHeqScale", value)),
+        }
+    }
+}
+
+impl From<AgcHeqScale> for u32 {
+    fn from(value: AgcHeqScale) -> Self {
+        value as u32
+    }
+}
+
+crate::lepton_cci::cci_words_via_u32!(AgcPolicy, AgcHeqScale);
+
+/// Region the AGC histogram is computed over (`0x0108`), inclusive pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgcRoi {
+    pub start_col: u16,
+    pub start_row: u16,
+    pub end_col: u16,
+    pub end_row: u16,
+}
+
+impl CciWords for AgcRoi {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(AgcRoi {
+            start_col: words[0],
+            start_row: words[1],
+            end_col: words[2],
+            end_row: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.start_col;
+        words[1] = self.start_row;
+        words[2] = self.end_col;
+        words[3] = self.end_row;
+    }
+}
+
+/// AGC histogram statistics (`0x010C`), computed over the [`AgcRoi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgcHistogramStats {
+    pub min_intensity: u16,
+    pub max_intensity: u16,
+    pub mean_intensity: u16,
+    pub num_pixels: u16,
+}
+
+impl CciWords for AgcHistogramStats {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(AgcHistogramStats {
+            min_intensity: words[0],
+            max_intensity: words[1],
+            mean_intensity: words[2],
+            num_pixels: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.min_intensity;
+        words[1] = self.max_intensity;
+        words[2] = self.mean_intensity;
+        words[3] = self.num_pixels;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lepton_command::LepCommand;
+
+    #[test]
+    fn heq_commands_match_idd_ids() {
+        assert_eq!(
+            LepCommand::get_agc_heq_dampening_factor().raw_command_id(),
+            0x0124
+        );
+        assert_eq!(
+            LepCommand::get_agc_heq_clip_limit_high().raw_command_id(),
+            0x012C
+        );
+        assert_eq!(
+            LepCommand::get_agc_heq_scale_factor().raw_command_id(),
+            0x0144
+        );
+    }
+
+    #[test]
+    fn roi_is_column_first() {
+        let roi = AgcRoi {
+            start_col: 0,
+            start_row: 1,
+            end_col: 159,
+            end_row: 119,
+        };
+        let mut words = [0u16; 4];
+        roi.to_words(&mut words);
+        assert_eq!(words, [0, 1, 159, 119]);
+        assert_eq!(AgcRoi::from_words(&words).unwrap(), roi);
+    }
+
+    #[test]
+    fn histogram_stats_decode_in_idd_order() {
+        let stats = AgcHistogramStats::from_words(&[100, 9000, 4000, 19200]).unwrap();
+        assert_eq!(stats.min_intensity, 100);
+        assert_eq!(stats.max_intensity, 9000);
+        assert_eq!(stats.mean_intensity, 4000);
+        assert_eq!(stats.num_pixels, 19200);
+    }
+}
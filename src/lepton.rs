@@ -1,17 +1,66 @@
 use core::fmt;
 
-use crate::lepton_cci::{CciError, LEPTONCCI};
+use crate::agc::{AgcHeqScale, AgcHistogramStats, AgcPolicy, AgcRoi};
+use crate::lepton_cci::{CciError, DecodeError, LEPTONCCI};
+use crate::lepton_command::LepCommand;
 use crate::lepton_status::LepStatus;
-use crate::oem::VideoOutputSource;
+use crate::oem::{
+    GpioMode, PartNumber, ShutterProfile, SoftwareRevision, UserDefaultsState, VideoOutputFormat,
+    VideoOutputSource,
+};
+use crate::rad::{SpotmeterRoi, SpotmeterValue, TLinearResolution, TShutterMode};
+use crate::recovery::{RecoveryCounters, RecoveryPolicy, RecoveryStep};
+use crate::sys::{
+    CentiKelvin, CustomerSerialNumber, FfcShutterMode, FfcState, FramesToAverage, GainMode,
+    GainModeObject, SceneRoi, SceneStatistics, SerialNumber, ShutterPosition, SysCameraStatus,
+    SysRunStatus, TelemetryLocation, TelemetryMode, Uptime,
+};
+use crate::vid::{FocusRoi, Polarity, PseudoColorLut, UserLut};
 use crate::vospi::{
-    capture_frame_into, required_frame_buffer_len, CaptureError, CapturedFrame, FrameDiagnostics,
-    FrameMeta, PacketSource, RobustCaptureConfig, SyncState,
+    capture_frame_into, capture_segments_into, required_frame_buffer_len,
+    required_packet_buffer_len, CaptureError, CapturedFrame, FrameDiagnostics, FrameMeta,
+    PacketSource, RobustCaptureConfig, SegmentSink, SyncState, MIN_RESYNC_IDLE_MS,
 };
+use crate::vsync::{NoVsync, VsyncWait};
+use core::convert::Infallible;
+use embedded_hal::digital::{ErrorType, OutputPin};
 use embedded_hal::spi::Operation;
 use embedded_hal::{delay::DelayNs, i2c::I2c, spi};
 
 const PACKET_SIZE_BYTES: usize = 164;
 const FRAME_PACKETS: usize = 60;
+/// Longest wait for a VSYNC edge: one Lepton 2.x frame period at ~27 Hz.
+const VSYNC_TIMEOUT_US: u32 = 40_000;
+/// RESET_L low time; the datasheet minimum is 5000 MCLK periods (200 us at 25 MHz).
+const RESET_PULSE_US: u32 = 1_000;
+/// Time after RESET_L is released before the camera starts answering on CCI.
+const BOOT_WAIT_MS: u32 = 950;
+/// Interval between boot status reads while the camera restarts.
+const BOOT_POLL_INTERVAL_MS: u32 = 10;
+
+macro_rules! forward_cci_get {
+    ($fn_name:ident, $ty:ty) => {
+        pub fn $fn_name(&mut self) -> Result<($ty, LepStatus), LeptonError<E1, SPI::Error>> {
+            self.cci.$fn_name().map_err(Self::map_cci_error)
+        }
+    };
+}
+
+macro_rules! forward_cci_set {
+    ($fn_name:ident, $ty:ty) => {
+        pub fn $fn_name(&mut self, value: $ty) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
+            self.cci.$fn_name(value).map_err(Self::map_cci_error)
+        }
+    };
+}
+
+macro_rules! forward_cci_run {
+    ($fn_name:ident) => {
+        pub fn $fn_name(&mut self) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
+            self.cci.$fn_name().map_err(Self::map_cci_error)
+        }
+    };
+}
 
 #[derive(Debug, Clone)]
 pub struct CameraCheckReport {
@@ -24,13 +73,39 @@ pub struct CameraCheckTestResult {
     pub name: String,
     pub ok: bool,
     pub details: String,
-    pub readback_source: Option<u16>,
+    pub readback_source: Option<VideoOutputSource>,
+}
+
+/// Placeholder for an optional control pin that is not wired.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoPin;
+
+impl ErrorType for NoPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
 
 /// Camera module
-pub struct Lepton<I2C, SPI, D> {
+pub struct Lepton<I2C, SPI, D, CS = NoPin, RST = NoPin, PWD = NoPin> {
     cci: LEPTONCCI<I2C, D>,
     spi: SPI,
+    /// Chip select driven outside `spi`, if any; only toggled for CS idle resync.
+    cs: CS,
+    /// RESET_L, active low; `None` until [`Lepton::with_reset_pin`].
+    reset: Option<RST>,
+    /// PWR_DWN_L, active low; `None` until [`Lepton::with_power_down_pin`].
+    power_down: Option<PWD>,
+    recovery_policy: RecoveryPolicy,
+    recovery_counters: RecoveryCounters,
     frame: Box<[u8; FRAME_PACKETS * PACKET_SIZE_BYTES]>,
     robust_config: RobustCaptureConfig,
     diagnostics: FrameDiagnostics,
@@ -42,35 +117,150 @@ pub struct Lepton<I2C, SPI, D> {
 impl<I2C, SPI, E1, D> Lepton<I2C, SPI, D>
 where
     I2C: I2c<Error = E1>,
-    SPI: spi::SpiDevice,
+    SPI: VospiPort,
     D: embedded_hal::delay::DelayNs,
     E1: core::fmt::Debug,
 {
-    fn map_cci_error(err: CciError<E1>) -> LeptonError<E1, SPI::Error> {
-        match err {
-            CciError::I2c(e) => LeptonError::I2c(e),
-            CciError::Timeout => LeptonError::Timeout,
-        }
-    }
-
     pub fn new(i2c: I2C, spi: SPI, delay: D) -> Result<Self, E1> {
         let cci = LEPTONCCI::new(i2c, delay)?;
         let robust_config = RobustCaptureConfig::default();
         Ok(Lepton {
             cci,
             spi,
+            cs: NoPin,
+            reset: None,
+            power_down: None,
+            recovery_policy: RecoveryPolicy::default(),
+            recovery_counters: RecoveryCounters::default(),
             frame: Box::new([0; FRAME_PACKETS * PACKET_SIZE_BYTES]),
             diagnostics: FrameDiagnostics::default(),
             sync_state: SyncState::Unsynced,
             first_valid_synced: false,
-            packet_buffer: vec![0; robust_config.packet_size_bytes],
+            packet_buffer: vec![0; required_packet_buffer_len(&robust_config)],
             robust_config,
         })
     }
+}
+
+impl<I2C, P, E1, D> Lepton<I2C, SourcePort<P>, D>
+where
+    I2C: I2c<Error = E1>,
+    P: PacketSource,
+    P::Error: fmt::Debug,
+    D: embedded_hal::delay::DelayNs,
+    E1: core::fmt::Debug,
+{
+    /// Reads VoSPI from `source` instead of an `SpiDevice`.
+    ///
+    /// Robust capture, diagnostics, recovery and `check_camera` work as usual; CCI still
+    /// goes over `i2c`.
+    pub fn with_packet_source(i2c: I2C, source: P, delay: D) -> Result<Self, E1> {
+        Self::new(i2c, SourcePort::new(source), delay)
+    }
+}
+
+impl<I2C, SPI, D, CS, RST, PWD> Lepton<I2C, SPI, D, CS, RST, PWD> {
+    /// The VoSPI port, e.g. to inspect a [`SourcePort`]'s packet source between captures.
+    pub fn vospi_port_mut(&mut self) -> &mut SPI {
+        &mut self.spi
+    }
+
+    /// Uses `cs` as the camera chip select during [`ResyncStrategy::CsIdle`] recovery.
+    ///
+    /// Only needed when CS is held low by a GPIO rather than toggled by the `SpiDevice`;
+    /// the pin is driven high for the idle period and low again afterwards.
+    ///
+    /// [`ResyncStrategy::CsIdle`]: crate::vospi::ResyncStrategy::CsIdle
+    pub fn with_cs_pin<P: OutputPin>(self, cs: P) -> Lepton<I2C, SPI, D, P, RST, PWD> {
+        Lepton {
+            cci: self.cci,
+            spi: self.spi,
+            cs,
+            reset: self.reset,
+            power_down: self.power_down,
+            recovery_policy: self.recovery_policy,
+            recovery_counters: self.recovery_counters,
+            frame: self.frame,
+            robust_config: self.robust_config,
+            diagnostics: self.diagnostics,
+            sync_state: self.sync_state,
+            first_valid_synced: self.first_valid_synced,
+            packet_buffer: self.packet_buffer,
+        }
+    }
+
+    /// Uses `reset` as the camera's RESET_L line, driven by [`Lepton::hard_reset`],
+    /// [`Lepton::power_up`] and [`RecoveryStep::HardwareReset`].
+    ///
+    /// The pin should already be high (camera running) when it is handed over.
+    pub fn with_reset_pin<P: OutputPin>(self, reset: P) -> Lepton<I2C, SPI, D, CS, P, PWD> {
+        Lepton {
+            cci: self.cci,
+            spi: self.spi,
+            cs: self.cs,
+            reset: Some(reset),
+            power_down: self.power_down,
+            recovery_policy: self.recovery_policy,
+            recovery_counters: self.recovery_counters,
+            frame: self.frame,
+            robust_config: self.robust_config,
+            diagnostics: self.diagnostics,
+            sync_state: self.sync_state,
+            first_valid_synced: self.first_valid_synced,
+            packet_buffer: self.packet_buffer,
+        }
+    }
+
+    /// Uses `power_down` as the camera's PWR_DWN_L line, driven by [`Lepton::power_down`]
+    /// and [`Lepton::power_up`].
+    ///
+    /// The pin should already be high (camera powered) when it is handed over.
+    pub fn with_power_down_pin<P: OutputPin>(
+        self,
+        power_down: P,
+    ) -> Lepton<I2C, SPI, D, CS, RST, P> {
+        Lepton {
+            cci: self.cci,
+            spi: self.spi,
+            cs: self.cs,
+            reset: self.reset,
+            power_down: Some(power_down),
+            recovery_policy: self.recovery_policy,
+            recovery_counters: self.recovery_counters,
+            frame: self.frame,
+            robust_config: self.robust_config,
+            diagnostics: self.diagnostics,
+            sync_state: self.sync_state,
+            first_valid_synced: self.first_valid_synced,
+            packet_buffer: self.packet_buffer,
+        }
+    }
+}
+
+impl<I2C, SPI, E1, D, CS, RST, PWD> Lepton<I2C, SPI, D, CS, RST, PWD>
+where
+    I2C: I2c<Error = E1>,
+    SPI: VospiPort,
+    D: embedded_hal::delay::DelayNs,
+    E1: core::fmt::Debug,
+    CS: OutputPin,
+    RST: OutputPin,
+    PWD: OutputPin,
+{
+    fn map_cci_error(err: CciError<E1>) -> LeptonError<E1, SPI::Error> {
+        match err {
+            CciError::I2c(e) => LeptonError::I2c(e),
+            CciError::Timeout => LeptonError::Timeout,
+            CciError::Decode(e) => LeptonError::Decode(e),
+            CciError::DataLength(words) => LeptonError::DataLength(words),
+            CciError::Camera(status) => LeptonError::Camera(status),
+        }
+    }
 
+    /// Sets the GPIO3 VSYNC phase delay, in lines (`-3..=3`).
     pub fn set_phase_delay(
         &mut self,
-        phase_delay: i16,
+        phase_delay: i32,
     ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
         self.cci
             .set_phase_delay(phase_delay)
@@ -78,34 +268,38 @@ where
         self.cci.get_status_code().map_err(Self::map_cci_error)
     }
 
-    pub fn get_phase_delay(&mut self) -> Result<(i16, LepStatus), LeptonError<E1, SPI::Error>> {
+    pub fn get_phase_delay(&mut self) -> Result<(i32, LepStatus), LeptonError<E1, SPI::Error>> {
         self.cci.get_phase_delay().map_err(Self::map_cci_error)
     }
 
     pub fn set_gpio_mode(
         &mut self,
-        gpio_mode: u16,
+        gpio_mode: GpioMode,
     ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
         self.cci
             .set_gpio_mode(gpio_mode)
             .map_err(Self::map_cci_error)
     }
 
-    pub fn get_gpio_mode(&mut self) -> Result<(u16, LepStatus), LeptonError<E1, SPI::Error>> {
+    pub fn get_gpio_mode(&mut self) -> Result<(GpioMode, LepStatus), LeptonError<E1, SPI::Error>> {
         self.cci.get_gpio_mode().map_err(Self::map_cci_error)
     }
 
+    /// Selects the VoSPI pixel format and switches robust capture to the matching packet size.
     pub fn set_video_output_format(
         &mut self,
-        format: u16,
+        format: VideoOutputFormat,
     ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
-        self.cci
+        let status = self
+            .cci
             .set_oem_video_output_format(format)
-            .map_err(Self::map_cci_error)
+            .map_err(Self::map_cci_error)?;
+        self.set_robust_config(self.robust_config.with_output_format(format));
+        Ok(status)
     }
     pub fn get_video_output_format(
         &mut self,
-    ) -> Result<(u16, LepStatus), LeptonError<E1, SPI::Error>> {
+    ) -> Result<(VideoOutputFormat, LepStatus), LeptonError<E1, SPI::Error>> {
         self.cci
             .get_oem_video_output_format()
             .map_err(Self::map_cci_error)
@@ -113,7 +307,7 @@ where
 
     pub fn set_video_output_source(
         &mut self,
-        source: u16,
+        source: VideoOutputSource,
     ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
         self.cci
             .set_oem_video_output_source(source)
@@ -122,7 +316,7 @@ where
 
     pub fn get_video_output_source(
         &mut self,
-    ) -> Result<(u16, LepStatus), LeptonError<E1, SPI::Error>> {
+    ) -> Result<(VideoOutputSource, LepStatus), LeptonError<E1, SPI::Error>> {
         self.cci
             .get_oem_video_output_source()
             .map_err(Self::map_cci_error)
@@ -153,17 +347,157 @@ where
         self.cci.get_interface_status().map_err(Self::map_cci_error)
     }
 
+    /// Enables or disables telemetry lines; enabling also selects their location.
+    ///
+    /// The robust capture config is updated to match, so telemetry lines are kept out of
+    /// the image and decoded into [`FrameMeta::telemetry`].
     pub fn set_telemetry_mode(
         &mut self,
-        mode: u16,
+        mode: TelemetryMode,
+    ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
+        let status = match mode.location() {
+            Some(location) => {
+                self.cci
+                    .set_sys_telemetry_location(location)
+                    .map_err(Self::map_cci_error)?;
+                self.cci
+                    .set_telemetry_mode(true)
+                    .map_err(Self::map_cci_error)?
+            }
+            None => self
+                .cci
+                .set_telemetry_mode(false)
+                .map_err(Self::map_cci_error)?,
+        };
+        self.robust_config.telemetry = mode;
+        Ok(status)
+    }
+
+    pub fn get_telemetry_mode(
+        &mut self,
+    ) -> Result<(TelemetryMode, LepStatus), LeptonError<E1, SPI::Error>> {
+        let (enabled, status) = self.cci.get_telemetry_mode().map_err(Self::map_cci_error)?;
+        if !enabled {
+            return Ok((TelemetryMode::Disabled, status));
+        }
+        let (location, status) = self
+            .cci
+            .get_sys_telemetry_location()
+            .map_err(Self::map_cci_error)?;
+        Ok((TelemetryMode::from(Some(location)), status))
+    }
+
+    /// See [`LEPTONCCI::set_raise_status_errors`].
+    pub fn set_raise_status_errors(&mut self, enabled: bool) {
+        self.cci.set_raise_status_errors(enabled);
+    }
+
+    /// Issues an arbitrary Run command; see [`LEPTONCCI::run_command`].
+    pub fn run_command(
+        &mut self,
+        command: LepCommand,
+        data: Option<&[u16]>,
     ) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
         self.cci
-            .set_telemetry_mode(mode)
+            .run_command(command, data)
             .map_err(Self::map_cci_error)
     }
 
-    pub fn get_telemetry_mode(&mut self) -> Result<(u16, LepStatus), LeptonError<E1, SPI::Error>> {
-        self.cci.get_telemetry_mode().map_err(Self::map_cci_error)
+    forward_cci_run!(run_sys_ping);
+    forward_cci_run!(run_sys_ffc);
+    forward_cci_run!(run_sys_frame_average);
+    forward_cci_get!(get_sys_camera_status, SysCameraStatus);
+    forward_cci_get!(get_sys_serial_number, SerialNumber);
+    forward_cci_get!(get_sys_customer_serial_number, CustomerSerialNumber);
+    forward_cci_get!(get_sys_uptime, Uptime);
+    forward_cci_get!(get_sys_aux_temperature, CentiKelvin);
+    forward_cci_get!(get_sys_fpa_temperature, CentiKelvin);
+    forward_cci_set!(set_sys_telemetry_location, TelemetryLocation);
+    forward_cci_get!(get_sys_telemetry_location, TelemetryLocation);
+    forward_cci_set!(set_sys_frames_to_average, FramesToAverage);
+    forward_cci_get!(get_sys_frames_to_average, FramesToAverage);
+    forward_cci_get!(get_sys_scene_statistics, SceneStatistics);
+    forward_cci_set!(set_sys_scene_roi, SceneRoi);
+    forward_cci_get!(get_sys_scene_roi, SceneRoi);
+    forward_cci_get!(get_sys_thermal_shutdown_count, u16);
+    forward_cci_set!(set_sys_shutter_position, ShutterPosition);
+    forward_cci_get!(get_sys_shutter_position, ShutterPosition);
+    forward_cci_set!(set_sys_ffc_shutter_mode, FfcShutterMode);
+    forward_cci_get!(get_sys_ffc_shutter_mode, FfcShutterMode);
+    forward_cci_get!(get_sys_ffc_status, SysRunStatus);
+    forward_cci_set!(set_sys_gain_mode, GainMode);
+    forward_cci_get!(get_sys_gain_mode, GainMode);
+    forward_cci_get!(get_sys_ffc_state, FfcState);
+    forward_cci_set!(set_sys_gain_mode_object, GainModeObject);
+    forward_cci_get!(get_sys_gain_mode_object, GainModeObject);
+
+    pub fn run_oem_power_down(&mut self) -> Result<(), LeptonError<E1, SPI::Error>> {
+        self.cci.run_oem_power_down().map_err(Self::map_cci_error)
+    }
+
+    forward_cci_run!(run_oem_reboot);
+    forward_cci_run!(run_oem_save_user_defaults);
+    forward_cci_run!(run_oem_restore_user_defaults);
+    forward_cci_get!(get_oem_flir_part_number, PartNumber);
+    forward_cci_get!(get_oem_customer_part_number, PartNumber);
+    forward_cci_get!(get_oem_software_revision, SoftwareRevision);
+    forward_cci_set!(set_oem_video_output_enable, bool);
+    forward_cci_get!(get_oem_video_output_enable, bool);
+    forward_cci_get!(get_oem_user_defaults_state, UserDefaultsState);
+    forward_cci_set!(set_oem_shutter_profile, ShutterProfile);
+    forward_cci_get!(get_oem_shutter_profile, ShutterProfile);
+    forward_cci_set!(set_oem_thermal_shutdown_enable, bool);
+    forward_cci_get!(get_oem_thermal_shutdown_enable, bool);
+
+    forward_cci_set!(set_vid_polarity, Polarity);
+    forward_cci_get!(get_vid_polarity, Polarity);
+    forward_cci_set!(set_vid_lut, PseudoColorLut);
+    forward_cci_get!(get_vid_lut, PseudoColorLut);
+    forward_cci_set!(set_vid_user_lut, UserLut);
+    forward_cci_get!(get_vid_user_lut, UserLut);
+    forward_cci_set!(set_vid_focus_calc_enable, bool);
+    forward_cci_get!(get_vid_focus_calc_enable, bool);
+    forward_cci_set!(set_vid_focus_roi, FocusRoi);
+    forward_cci_get!(get_vid_focus_roi, FocusRoi);
+    forward_cci_set!(set_vid_focus_threshold, u32);
+    forward_cci_get!(get_vid_focus_threshold, u32);
+    forward_cci_get!(get_vid_focus_metric, u32);
+    forward_cci_set!(set_vid_sbnuc_enable, bool);
+    forward_cci_get!(get_vid_sbnuc_enable, bool);
+    forward_cci_set!(set_vid_freeze, bool);
+    forward_cci_get!(get_vid_freeze, bool);
+
+    forward_cci_run!(run_rad_ffc);
+    forward_cci_set!(set_rad_enable, bool);
+    forward_cci_get!(get_rad_enable, bool);
+    forward_cci_set!(set_rad_tshutter_mode, TShutterMode);
+    forward_cci_get!(get_rad_tshutter_mode, TShutterMode);
+    forward_cci_set!(set_rad_tshutter, CentiKelvin);
+    forward_cci_get!(get_rad_tshutter, CentiKelvin);
+    forward_cci_set!(set_rad_tlinear_enable, bool);
+    forward_cci_get!(get_rad_tlinear_enable, bool);
+    forward_cci_set!(set_rad_tlinear_resolution, TLinearResolution);
+    forward_cci_get!(get_rad_tlinear_resolution, TLinearResolution);
+    forward_cci_set!(set_rad_tlinear_auto_resolution, bool);
+    forward_cci_get!(get_rad_tlinear_auto_resolution, bool);
+    forward_cci_set!(set_rad_spotmeter_roi, SpotmeterRoi);
+    forward_cci_get!(get_rad_spotmeter_roi, SpotmeterRoi);
+    forward_cci_get!(get_rad_spotmeter_value, SpotmeterValue);
+
+    /// Returns the active TLinear scale when both radiometry and TLinear output are enabled.
+    ///
+    /// `None` means VoSPI pixels are not TLinear temperatures and must not be converted.
+    pub fn tlinear_resolution_if_active(
+        &mut self,
+    ) -> Result<Option<TLinearResolution>, LeptonError<E1, SPI::Error>> {
+        let (radiometry, _) = self.get_rad_enable()?;
+        let (tlinear, _) = self.get_rad_tlinear_enable()?;
+        if !(radiometry && tlinear) {
+            return Ok(None);
+        }
+
+        let (resolution, _) = self.get_rad_tlinear_resolution()?;
+        Ok(Some(resolution))
     }
 
     pub fn get_agc_enable(&mut self) -> Result<(u16, LepStatus), LeptonError<E1, SPI::Error>> {
@@ -174,6 +508,28 @@ where
         self.cci.set_agc_enable(mode).map_err(Self::map_cci_error)
     }
 
+    forward_cci_set!(set_agc_policy, AgcPolicy);
+    forward_cci_get!(get_agc_policy, AgcPolicy);
+    forward_cci_set!(set_agc_roi, AgcRoi);
+    forward_cci_get!(get_agc_roi, AgcRoi);
+    forward_cci_get!(get_agc_histogram_statistics, AgcHistogramStats);
+    forward_cci_set!(set_agc_calc_enable, bool);
+    forward_cci_get!(get_agc_calc_enable, bool);
+    forward_cci_set!(set_agc_linear_dampening_factor, u16);
+    forward_cci_get!(get_agc_linear_dampening_factor, u16);
+    forward_cci_set!(set_agc_heq_dampening_factor, u16);
+    forward_cci_get!(get_agc_heq_dampening_factor, u16);
+    forward_cci_set!(set_agc_heq_clip_limit_high, u16);
+    forward_cci_get!(get_agc_heq_clip_limit_high, u16);
+    forward_cci_set!(set_agc_heq_clip_limit_low, u16);
+    forward_cci_get!(get_agc_heq_clip_limit_low, u16);
+    forward_cci_set!(set_agc_heq_empty_counts, u16);
+    forward_cci_get!(get_agc_heq_empty_counts, u16);
+    forward_cci_set!(set_agc_heq_scale_factor, AgcHeqScale);
+    forward_cci_get!(get_agc_heq_scale_factor, AgcHeqScale);
+    forward_cci_set!(set_agc_heq_linear_percent, u16);
+    forward_cci_get!(get_agc_heq_linear_percent, u16);
+
     /// Runs an end-to-end camera health check by programming deterministic OEM
     /// video output source patterns and validating one robust VoSPI frame for each.
     ///
@@ -181,7 +537,18 @@ where
     /// source select (`0x0800:0x2C/0x2D`) and source constant value (`0x0800:0x3C/0x3D`).
     /// The report is non-panicking and includes likely failure causes for CCI,
     /// VoSPI framing/timing, and payload byte-order interpretation.
+    ///
+    /// Camera status errors are always raised during the check, so a rejected set is
+    /// reported with its status code rather than surfacing as a readback mismatch.
     pub fn check_camera(&mut self) -> CameraCheckReport {
+        let raise_status_errors = self.cci.raise_status_errors();
+        self.cci.set_raise_status_errors(true);
+        let report = self.check_camera_inner();
+        self.cci.set_raise_status_errors(raise_status_errors);
+        report
+    }
+
+    fn check_camera_inner(&mut self) -> CameraCheckReport {
         let mut report = CameraCheckReport {
             tests: Vec::new(),
             restored: true,
@@ -251,7 +618,7 @@ where
             }
         }
 
-        if let Err(err) = self.set_video_output_source(source as u16) {
+        if let Err(err) = self.set_video_output_source(source) {
             return CameraCheckTestResult {
                 name: name.to_string(),
                 ok: false,
@@ -272,7 +639,7 @@ where
             }
         };
 
-        if readback_source != source as u16 {
+        if readback_source != source {
             return CameraCheckTestResult {
                 name: name.to_string(),
                 ok: false,
@@ -298,13 +665,16 @@ where
                 },
             };
 
+        let format = PixelLayout::from(self.robust_config.output_format);
         let payload_bytes_per_packet = self.robust_config.packet_size_bytes.saturating_sub(4);
-        let cols = payload_bytes_per_packet / 2;
+        let cols = payload_bytes_per_packet / format.bytes_per_pixel();
         let rows = self.robust_config.lines_per_segment * self.robust_config.segments_per_frame;
 
-        let (ok, mut details) = validate_pattern(&frame.pixels, source, cols, rows);
-        if !ok {
-            let (swapped_ok, _) = validate_pattern_swapped(&frame.pixels, source, cols, rows);
+        let (ok, mut details) = validate_pattern(&frame.pixels, source, format, cols, rows);
+        // Byte order only matters for 16-bit pixels.
+        if !ok && format == PixelLayout::Raw14 {
+            let (swapped_ok, _) =
+                validate_pattern(&frame.pixels, source, PixelLayout::Raw14Swapped, cols, rows);
             if swapped_ok {
                 details.push_str("; likely endianness/word-order issue");
             }
@@ -338,17 +708,19 @@ where
         frame[..PACKET_SIZE_BYTES].copy_from_slice(&first_packet);
 
         self.spi
-            .read(&mut frame[PACKET_SIZE_BYTES..])
+            .read_packets(&mut frame[PACKET_SIZE_BYTES..], PACKET_SIZE_BYTES)
             .map_err(LeptonError::Spi)?;
 
         Ok(frame)
     }
 
-    /// Configure robust VoSPI acquisition behavior for Lepton 3.x/3.5.
+    /// Configure robust VoSPI acquisition behavior.
+    ///
+    /// Defaults to Lepton 3.x/3.5; use [`RobustCaptureConfig::for_sensor`] for Lepton 2.x.
     pub fn set_robust_config(&mut self, config: RobustCaptureConfig) {
         self.robust_config = config;
         self.packet_buffer
-            .resize(self.robust_config.packet_size_bytes, 0);
+            .resize(required_packet_buffer_len(&self.robust_config), 0);
     }
 
     /// Returns the active robust VoSPI acquisition configuration.
@@ -361,7 +733,216 @@ where
         self.diagnostics
     }
 
-    /// Acquires one robustly validated frame (Lepton 3.x/3.5) and metadata.
+    /// Sets the steps taken when robust capture fails on the VoSPI stream; see
+    /// [`crate::recovery`].
+    pub fn set_recovery_policy(&mut self, policy: RecoveryPolicy) {
+        self.recovery_policy = policy;
+    }
+
+    pub fn recovery_policy(&self) -> RecoveryPolicy {
+        self.recovery_policy
+    }
+
+    /// Returns cumulative counters of recovery steps and their outcomes.
+    pub fn recovery_counters(&self) -> RecoveryCounters {
+        self.recovery_counters
+    }
+
+    /// Runs a single recovery step now, regardless of the policy.
+    ///
+    /// Capture restarts from an unsynchronised state afterwards.
+    ///
+    /// [`RecoveryStep::HardwareReset`] without a reset pin fails with
+    /// [`LeptonError::PinNotConfigured`] and is not counted.
+    pub fn recover(&mut self, step: RecoveryStep) -> Result<(), LeptonError<E1, SPI::Error>> {
+        if step == RecoveryStep::HardwareReset && self.reset.is_none() {
+            return Err(LeptonError::PinNotConfigured);
+        }
+        self.recovery_counters.record(step);
+        self.sync_state = SyncState::Unsynced;
+        self.first_valid_synced = false;
+
+        let result = match step {
+            RecoveryStep::IdleResync => {
+                let mut source = PortSource {
+                    spi: &mut self.spi,
+                    cs: &mut self.cs,
+                    delay: self.cci.delay_mut(),
+                    vsync: &mut NoVsync,
+                    inter_packet_delay_us: 0,
+                    inter_packet_delay_discard_us: 0,
+                };
+                source
+                    .resync_idle(self.recovery_policy.idle_ms.max(MIN_RESYNC_IDLE_MS))
+                    .map_err(LeptonError::from_port)
+            }
+            RecoveryStep::CciReboot => self
+                .cci
+                .run_oem_reboot()
+                .map_err(Self::map_cci_error)
+                .and_then(|_| self.wait_for_boot(self.recovery_policy.boot_timeout_ms)),
+            RecoveryStep::HardwareReset => self.hard_reset(),
+        };
+
+        if result.is_err() {
+            self.recovery_counters.failed_steps += 1;
+        }
+        result
+    }
+
+    /// Pulses RESET_L and waits until the camera has booted.
+    ///
+    /// Follows the datasheet sequence: RESET_L low for at least 5000 MCLK periods, then
+    /// about a second of boot time before the boot status is polled, for up to the recovery
+    /// policy's `boot_timeout_ms`. Needs [`with_reset_pin`](Lepton::with_reset_pin), else
+    /// fails with [`LeptonError::PinNotConfigured`].
+    pub fn hard_reset(&mut self) -> Result<(), LeptonError<E1, SPI::Error>> {
+        let reset = self.reset.as_mut().ok_or(LeptonError::PinNotConfigured)?;
+        self.sync_state = SyncState::Unsynced;
+        self.first_valid_synced = false;
+
+        reset.set_low().map_err(|_| LeptonError::Pin)?;
+        self.cci.delay_mut().delay_us(RESET_PULSE_US);
+        reset.set_high().map_err(|_| LeptonError::Pin)?;
+        self.boot()
+    }
+
+    /// Puts the camera in power-down by pulling PWR_DWN_L low.
+    ///
+    /// Settings that were not saved with `run_oem_save_user_defaults` are lost. Needs
+    /// [`with_power_down_pin`](Lepton::with_power_down_pin), else fails with
+    /// [`LeptonError::PinNotConfigured`].
+    pub fn power_down(&mut self) -> Result<(), LeptonError<E1, SPI::Error>> {
+        let power_down = self
+            .power_down
+            .as_mut()
+            .ok_or(LeptonError::PinNotConfigured)?;
+        self.sync_state = SyncState::Unsynced;
+        self.first_valid_synced = false;
+
+        power_down.set_low().map_err(|_| LeptonError::Pin)
+    }
+
+    /// Wakes the camera from power-down and waits until it has booted.
+    ///
+    /// RESET_L is held low while PWR_DWN_L is released, then released after the reset pulse
+    /// width, as in the datasheet power-on sequence; boot is awaited as in
+    /// [`hard_reset`](Self::hard_reset). Without a reset pin only PWR_DWN_L is released.
+    /// Fails with [`LeptonError::PinNotConfigured`] without a power-down pin.
+    pub fn power_up(&mut self) -> Result<(), LeptonError<E1, SPI::Error>> {
+        let power_down = self
+            .power_down
+            .as_mut()
+            .ok_or(LeptonError::PinNotConfigured)?;
+        self.sync_state = SyncState::Unsynced;
+        self.first_valid_synced = false;
+
+        if let Some(reset) = self.reset.as_mut() {
+            reset.set_low().map_err(|_| LeptonError::Pin)?;
+        }
+        power_down.set_high().map_err(|_| LeptonError::Pin)?;
+        self.cci.delay_mut().delay_us(RESET_PULSE_US);
+        if let Some(reset) = self.reset.as_mut() {
+            reset.set_high().map_err(|_| LeptonError::Pin)?;
+        }
+        self.boot()
+    }
+
+    /// Waits out the boot time after RESET_L is released, then polls the boot status.
+    fn boot(&mut self) -> Result<(), LeptonError<E1, SPI::Error>> {
+        self.cci.delay_mut().delay_ms(BOOT_WAIT_MS);
+        self.wait_for_boot(self.recovery_policy.boot_timeout_ms)
+    }
+
+    /// Polls the CCI boot status until the camera reports booted and idle.
+    ///
+    /// Bus errors count as not booted yet, since the camera NACKs while it starts.
+    fn wait_for_boot(&mut self, timeout_ms: u32) -> Result<(), LeptonError<E1, SPI::Error>> {
+        let mut waited_ms = 0;
+        loop {
+            if let (Ok(true), Ok(true)) =
+                (self.cci.get_boot_status(), self.cci.get_interface_status())
+            {
+                return Ok(());
+            }
+            if waited_ms >= timeout_ms {
+                return Err(LeptonError::Timeout);
+            }
+            self.cci.delay_mut().delay_ms(BOOT_POLL_INTERVAL_MS);
+            waited_ms += BOOT_POLL_INTERVAL_MS;
+        }
+    }
+
+    /// Runs `capture`, escalating through the recovery policy while it fails with an error
+    /// that recovery can address.
+    ///
+    /// Framing errors on a locked stream are returned by capture without retrying, so they
+    /// get one plain retry, seeking the next frame, before the first step.
+    fn with_recovery<R>(
+        &mut self,
+        mut capture: impl FnMut(&mut Self) -> Result<R, LeptonError<E1, SPI::Error>>,
+    ) -> Result<R, LeptonError<E1, SPI::Error>> {
+        let has_reset_pin = self.reset.is_some();
+        let mut steps = self
+            .recovery_policy
+            .steps()
+            .filter(move |step| *step != RecoveryStep::HardwareReset || has_reset_pin)
+            .peekable();
+        let can_recover = steps.peek().is_some();
+        let mut escalated = false;
+        let mut retried = false;
+
+        loop {
+            match capture(self) {
+                Err(
+                    LeptonError::CrcMismatch
+                    | LeptonError::LineOutOfOrder
+                    | LeptonError::SegmentOutOfOrder,
+                ) if can_recover && !retried && !escalated => {
+                    // The stream is mid-frame now, so look for the next frame start.
+                    self.first_valid_synced = false;
+                    retried = true;
+                }
+                Err(
+                    err @ (LeptonError::SyncLost
+                    | LeptonError::RetryLimitExceeded
+                    | LeptonError::DiscardPacketFlood
+                    | LeptonError::Timeout
+                    | LeptonError::CrcMismatch
+                    | LeptonError::LineOutOfOrder
+                    | LeptonError::SegmentOutOfOrder),
+                ) => {
+                    // A failed step moves straight on to the next one.
+                    let recovered = loop {
+                        match steps.next() {
+                            Some(step) => {
+                                escalated = true;
+                                if self.recover(step).is_ok() {
+                                    break true;
+                                }
+                            }
+                            None => break false,
+                        }
+                    };
+                    if !recovered {
+                        if escalated {
+                            self.recovery_counters.unrecovered += 1;
+                        }
+                        return Err(err);
+                    }
+                }
+                Ok(value) => {
+                    if escalated {
+                        self.recovery_counters.recovered += 1;
+                    }
+                    return Ok(value);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Acquires one robustly validated frame and metadata.
     pub fn read_frame_with_meta(&mut self) -> Result<CapturedFrame, LeptonError<E1, SPI::Error>> {
         self.read_frame_robust()
     }
@@ -382,72 +963,113 @@ where
     pub fn read_frame_robust_into_with_ticks<F>(
         &mut self,
         out: &mut [u8],
-        mut now_ticks: F,
+        now_ticks: F,
     ) -> Result<FrameMeta, LeptonError<E1, SPI::Error>>
     where
         F: FnMut() -> u64,
     {
-        struct SpiSource<'a, S, D> {
-            spi: &'a mut S,
-            delay: &'a mut D,
-            inter_packet_delay_us: u32,
-            inter_packet_delay_discard_us: u32,
-        }
-
-        impl<S, D> PacketSource for SpiSource<'_, S, D>
-        where
-            S: spi::SpiDevice,
-            D: DelayNs,
-        {
-            type Error = S::Error;
-
-            fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
-                self.spi.transaction(&mut [Operation::Read(packet)])?;
-
-                // Apply inter-packet timing at the packet source boundary so every read path in
-                // robust capture (normal, discard/backoff, and resync) gets identical behavior.
-                let delay_us = if self.inter_packet_delay_discard_us > 0
-                    && crate::vospi::is_discard_packet(packet)
-                {
-                    self.inter_packet_delay_discard_us
-                } else {
-                    self.inter_packet_delay_us
-                };
+        self.read_frame_robust_into_vsync_with_ticks(&mut NoVsync, out, now_ticks)
+    }
 
-                if delay_us > 0 {
-                    self.delay.delay_us(delay_us);
-                }
+    /// Switches GPIO3 to the VSYNC output used by the `*_vsync` capture methods.
+    pub fn enable_vsync(&mut self) -> Result<LepStatus, LeptonError<E1, SPI::Error>> {
+        self.set_gpio_mode(GpioMode::Vsync)
+    }
 
-                Ok(())
-            }
-        }
+    /// Acquires one robustly validated frame, starting each segment on a VSYNC edge.
+    ///
+    /// Call [`enable_vsync`](Self::enable_vsync) first. If no edge arrives within a frame
+    /// period, the segment is read without waiting.
+    pub fn read_frame_robust_vsync<V: VsyncWait>(
+        &mut self,
+        vsync: &mut V,
+    ) -> Result<CapturedFrame, LeptonError<E1, SPI::Error>> {
+        let mut frame = vec![0; required_frame_buffer_len(&self.robust_config)];
+        let meta = self.read_frame_robust_into_vsync_with_ticks(vsync, &mut frame, || 0)?;
 
+        Ok(CapturedFrame {
+            pixels: frame,
+            meta,
+        })
+    }
+
+    /// Allocation-free VSYNC-driven robust capture; see
+    /// [`read_frame_robust_vsync`](Self::read_frame_robust_vsync).
+    pub fn read_frame_robust_into_vsync_with_ticks<V, F>(
+        &mut self,
+        vsync: &mut V,
+        out: &mut [u8],
+        mut now_ticks: F,
+    ) -> Result<FrameMeta, LeptonError<E1, SPI::Error>>
+    where
+        V: VsyncWait,
+        F: FnMut() -> u64,
+    {
         let required = required_frame_buffer_len(&self.robust_config);
         if out.len() < required {
             return Err(LeptonError::InvalidPacket);
         }
 
-        let mut source = SpiSource {
-            spi: &mut self.spi,
-            delay: self.cci.delay_mut(),
-            inter_packet_delay_us: self.robust_config.inter_packet_delay_us,
-            inter_packet_delay_discard_us: self.robust_config.inter_packet_delay_discard_us,
-        };
+        self.with_recovery(|lepton| {
+            let mut source = PortSource {
+                spi: &mut lepton.spi,
+                cs: &mut lepton.cs,
+                delay: lepton.cci.delay_mut(),
+                vsync: &mut *vsync,
+                inter_packet_delay_us: lepton.robust_config.inter_packet_delay_us,
+                inter_packet_delay_discard_us: lepton.robust_config.inter_packet_delay_discard_us,
+            };
+
+            capture_frame_into(
+                &mut source,
+                &lepton.robust_config,
+                &mut lepton.first_valid_synced,
+                &mut lepton.sync_state,
+                &mut lepton.diagnostics,
+                out,
+                &mut lepton.packet_buffer,
+                &mut now_ticks,
+            )
+            .map_err(LeptonError::from_capture)
+        })
+    }
+
+    /// Robust capture that streams each validated segment to `sink`.
+    ///
+    /// Only `segment_buf` ([`crate::vospi::required_segment_buffer_len`] bytes) is needed instead of a whole
+    /// frame; see [`capture_segments_into`] for delivery semantics. If the recovery policy
+    /// retries the capture, `sink` may already have received segments of the failed attempt.
+    pub fn read_frame_segments<K: SegmentSink>(
+        &mut self,
+        segment_buf: &mut [u8],
+        sink: &mut K,
+    ) -> Result<FrameMeta, LeptonError<E1, SPI::Error>> {
+        self.with_recovery(|lepton| {
+            let mut source = PortSource {
+                spi: &mut lepton.spi,
+                cs: &mut lepton.cs,
+                delay: lepton.cci.delay_mut(),
+                vsync: &mut NoVsync,
+                inter_packet_delay_us: lepton.robust_config.inter_packet_delay_us,
+                inter_packet_delay_discard_us: lepton.robust_config.inter_packet_delay_discard_us,
+            };
 
-        capture_frame_into(
-            &mut source,
-            &self.robust_config,
-            &mut self.first_valid_synced,
-            &mut self.sync_state,
-            &mut self.diagnostics,
-            out,
-            &mut self.packet_buffer,
-            || now_ticks(),
-        )
-        .map_err(LeptonError::from_capture)
+            capture_segments_into(
+                &mut source,
+                &lepton.robust_config,
+                &mut lepton.first_valid_synced,
+                &mut lepton.sync_state,
+                &mut lepton.diagnostics,
+                segment_buf,
+                &mut lepton.packet_buffer,
+                || 0,
+                sink,
+            )
+            .map_err(LeptonError::from_capture)
+        })
     }
 
-    /// Acquires one robustly validated frame (Lepton 3.x/3.5).
+    /// Acquires one robustly validated frame.
     pub fn read_frame_robust(&mut self) -> Result<CapturedFrame, LeptonError<E1, SPI::Error>> {
         let mut frame = vec![0; required_frame_buffer_len(&self.robust_config)];
         let meta = self.read_frame_robust_into(&mut frame)?;
@@ -471,12 +1093,15 @@ where
 
     fn check_packet(&mut self) -> Result<[u8; PACKET_SIZE_BYTES], LeptonError<E1, SPI::Error>> {
         let mut packet = [0_u8; PACKET_SIZE_BYTES];
-        self.spi.read(&mut packet).map_err(LeptonError::Spi)?;
+        self.spi
+            .read_packet(&mut packet)
+            .map_err(LeptonError::Spi)?;
 
         Ok(packet)
     }
 
     /// Returns a box containing the frame data as an array.
+    #[allow(clippy::borrowed_box)]
     pub fn get_frame(&mut self) -> &Box<[u8; FRAME_PACKETS * PACKET_SIZE_BYTES]> {
         &self.frame
     }
@@ -491,84 +1116,307 @@ where
     }
 }
 
-fn pixel_at(frame: &[u8], cols: usize, row: usize, col: usize, swapped: bool) -> u16 {
-    let byte_index = (row * cols + col) * 2;
-    let raw = if swapped {
-        u16::from_le_bytes([frame[byte_index], frame[byte_index + 1]])
-    } else {
-        u16::from_be_bytes([frame[byte_index], frame[byte_index + 1]])
-    };
-    raw & 0x3FFF
+/// Transport `Lepton` reads VoSPI packets from.
+///
+/// Implemented for every `SpiDevice`, with one transaction per packet. Any other
+/// [`PacketSource`] (a DMA-backed reader, spidev with large transfers, a replay log or a
+/// simulator) can be used through [`SourcePort`].
+pub trait VospiPort {
+    type Error: fmt::Debug;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Fills `packets` with consecutive packets of `packet_size` bytes.
+    ///
+    /// Used by the legacy [`Lepton::read_frame`] and by robust capture with
+    /// [`RobustCaptureConfig::bulk_read_packets`]; the default reads them one by one.
+    fn read_packets(&mut self, packets: &mut [u8], packet_size: usize) -> Result<(), Self::Error> {
+        for packet in packets.chunks_mut(packet_size) {
+            self.read_packet(packet)?;
+        }
+        Ok(())
+    }
+
+    /// Called before each segment, after any VSYNC wait; see
+    /// [`PacketSource::wait_segment_start`].
+    fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Holds the bus idle for `idle_ms` if the port can, returning `false` otherwise.
+    ///
+    /// When it returns `false`, `Lepton` runs the idle itself: it raises its CS pin, if any,
+    /// and waits with its delay.
+    fn resync_idle(&mut self, _idle_ms: u32) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
+/// Bulk reads are a single `Operation::Read`, so CS stays asserted across the packets.
+impl<S: spi::SpiDevice> VospiPort for S {
+    type Error = S::Error;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(&mut [Operation::Read(packet)])
+    }
+
+    fn read_packets(&mut self, packets: &mut [u8], _packet_size: usize) -> Result<(), Self::Error> {
+        self.read(packets)
+    }
+}
+
+/// Adapts a [`PacketSource`] to [`VospiPort`]; see [`Lepton::with_packet_source`].
+pub struct SourcePort<P> {
+    source: P,
+}
+
+impl<P: PacketSource> SourcePort<P> {
+    pub fn new(source: P) -> Self {
+        SourcePort { source }
+    }
+
+    pub fn source(&self) -> &P {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut P {
+        &mut self.source
+    }
+
+    pub fn release(self) -> P {
+        self.source
+    }
+}
+
+impl<P> VospiPort for SourcePort<P>
+where
+    P: PacketSource,
+    P::Error: fmt::Debug,
+{
+    type Error = P::Error;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        self.source.read_packet(packet)
+    }
+
+    fn read_packets(&mut self, packets: &mut [u8], packet_size: usize) -> Result<(), Self::Error> {
+        self.source.read_packets(packets, packet_size)
+    }
+
+    fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+        self.source.wait_segment_start()
+    }
+
+    fn resync_idle(&mut self, idle_ms: u32) -> Result<bool, Self::Error> {
+        self.source.resync_idle(idle_ms)?;
+        Ok(true)
+    }
+}
+
+/// Error from a [`PortSource`]: the VoSPI port itself, or the external CS pin.
+#[derive(Debug)]
+enum PortError<E> {
+    Port(E),
+    Cs,
+}
+
+/// VoSPI packet source over the camera's [`VospiPort`], used by the robust capture paths.
+struct PortSource<'a, S, C, D, V> {
+    spi: &'a mut S,
+    cs: &'a mut C,
+    delay: &'a mut D,
+    vsync: &'a mut V,
+    inter_packet_delay_us: u32,
+    inter_packet_delay_discard_us: u32,
+}
+
+impl<S, C, D, V> PacketSource for PortSource<'_, S, C, D, V>
+where
+    S: VospiPort,
+    C: OutputPin,
+    D: DelayNs,
+    V: VsyncWait,
+{
+    type Error = PortError<S::Error>;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        self.spi.read_packet(packet).map_err(PortError::Port)?;
+
+        // Apply inter-packet timing at the packet source boundary so every read path in
+        // robust capture (normal, discard/backoff, and resync) gets identical behavior.
+        let delay_us =
+            if self.inter_packet_delay_discard_us > 0 && crate::vospi::is_discard_packet(packet) {
+                self.inter_packet_delay_discard_us
+            } else {
+                self.inter_packet_delay_us
+            };
+
+        if delay_us > 0 {
+            self.delay.delay_us(delay_us);
+        }
+
+        Ok(())
+    }
+
+    fn read_packets(&mut self, packets: &mut [u8], packet_size: usize) -> Result<(), Self::Error> {
+        // Inter-packet delays need a transfer per packet.
+        if self.inter_packet_delay_us > 0 || self.inter_packet_delay_discard_us > 0 {
+            for packet in packets.chunks_exact_mut(packet_size) {
+                self.read_packet(packet)?;
+            }
+            return Ok(());
+        }
+
+        self.spi
+            .read_packets(packets, packet_size)
+            .map_err(PortError::Port)
+    }
+
+    fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+        // A missed edge falls back to reading without synchronisation.
+        self.vsync.wait_vsync(self.delay, VSYNC_TIMEOUT_US);
+        self.spi.wait_segment_start().map_err(PortError::Port)
+    }
+
+    fn resync_idle(&mut self, idle_ms: u32) -> Result<(), Self::Error> {
+        if self.spi.resync_idle(idle_ms).map_err(PortError::Port)? {
+            return Ok(());
+        }
+
+        // No transaction is issued while waiting, so the SpiDevice keeps its own CS released
+        // and SCK idle; an external CS pin is released for the wait.
+        self.cs.set_high().map_err(|_| PortError::Cs)?;
+        self.delay.delay_ms(idle_ms);
+        self.cs.set_low().map_err(|_| PortError::Cs)
+    }
+}
+
+/// How `check_camera` interprets captured pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PixelLayout {
+    Raw14,
+    /// RAW14 with the bytes of each pixel swapped, to diagnose byte-order mistakes.
+    Raw14Swapped,
+    /// Colorized output; test patterns pass through AGC and the LUT, so only equality
+    /// between pixels is meaningful, not their ordering.
+    Rgb888,
+}
+
+impl From<VideoOutputFormat> for PixelLayout {
+    fn from(value: VideoOutputFormat) -> Self {
+        match value {
+            VideoOutputFormat::Rgb888 => PixelLayout::Rgb888,
+            _ => PixelLayout::Raw14,
+        }
+    }
+}
+
+impl PixelLayout {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelLayout::Rgb888 => 3,
+            PixelLayout::Raw14 | PixelLayout::Raw14Swapped => 2,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> u32 {
+        match self {
+            PixelLayout::Raw14 => (u16::from_be_bytes([bytes[0], bytes[1]]) & 0x3FFF) as u32,
+            PixelLayout::Raw14Swapped => (u16::from_le_bytes([bytes[0], bytes[1]]) & 0x3FFF) as u32,
+            PixelLayout::Rgb888 => u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]),
+        }
+    }
+
+    /// Whether `last` continues a ramp that started at `first`.
+    fn ramps(self, first: u32, last: u32) -> bool {
+        match self {
+            PixelLayout::Rgb888 => last != first,
+            PixelLayout::Raw14 | PixelLayout::Raw14Swapped => last > first,
+        }
+    }
+}
+
+fn pixel_at(frame: &[u8], format: PixelLayout, cols: usize, row: usize, col: usize) -> u32 {
+    let bytes_per_pixel = format.bytes_per_pixel();
+    let byte_index = (row * cols + col) * bytes_per_pixel;
+    format.decode(&frame[byte_index..byte_index + bytes_per_pixel])
 }
 
-fn validate_constant(frame: &[u8], swapped: bool, cols: usize, rows: usize) -> (bool, String) {
-    let expected_bytes = rows.saturating_mul(cols).saturating_mul(2);
+fn check_geometry(
+    frame: &[u8],
+    format: PixelLayout,
+    cols: usize,
+    rows: usize,
+) -> Result<usize, String> {
+    let expected_bytes = rows
+        .saturating_mul(cols)
+        .saturating_mul(format.bytes_per_pixel());
     if cols == 0 || rows == 0 {
-        return (
-            false,
-            format!("invalid geometry rows={} cols={}", rows, cols),
-        );
+        return Err(format!("invalid geometry rows={} cols={}", rows, cols));
     }
     if frame.len() < expected_bytes {
-        return (
-            false,
-            format!(
-                "payload too small for geometry: got {} bytes, need at least {}",
-                frame.len(),
-                expected_bytes
-            ),
-        );
+        return Err(format!(
+            "payload too small for geometry: got {} bytes, need at least {}",
+            frame.len(),
+            expected_bytes
+        ));
     }
+    Ok(expected_bytes)
+}
+
+fn validate_constant(
+    frame: &[u8],
+    format: PixelLayout,
+    cols: usize,
+    rows: usize,
+) -> (bool, String) {
+    let expected_bytes = match check_geometry(frame, format, cols, rows) {
+        Ok(expected_bytes) => expected_bytes,
+        Err(details) => return (false, details),
+    };
 
-    let mut min_value = u16::MAX;
-    let mut max_value = u16::MIN;
+    // RGB channels are compared independently; a RAW14 value fills all three lanes.
+    let mut min_value = [u16::MAX; 3];
+    let mut max_value = [u16::MIN; 3];
 
-    for chunk in frame[..expected_bytes].chunks_exact(2) {
-        let value = if swapped {
-            u16::from_le_bytes([chunk[0], chunk[1]])
-        } else {
-            u16::from_be_bytes([chunk[0], chunk[1]])
-        } & 0x3FFF;
-        min_value = min_value.min(value);
-        max_value = max_value.max(value);
+    for chunk in frame[..expected_bytes].chunks_exact(format.bytes_per_pixel()) {
+        let lanes = match format {
+            PixelLayout::Rgb888 => [chunk[0] as u16, chunk[1] as u16, chunk[2] as u16],
+            PixelLayout::Raw14 | PixelLayout::Raw14Swapped => [format.decode(chunk) as u16; 3],
+        };
+        for (lane, value) in lanes.into_iter().enumerate() {
+            min_value[lane] = min_value[lane].min(value);
+            max_value[lane] = max_value[lane].max(value);
+        }
     }
 
-    let spread = max_value.saturating_sub(min_value);
+    let spread = (0..3)
+        .map(|lane| max_value[lane].saturating_sub(min_value[lane]))
+        .max()
+        .unwrap_or(0);
     (
         spread <= 2,
         format!(
             "constant spread={} (min={}, max={})",
-            spread, min_value, max_value
+            spread,
+            min_value.iter().min().copied().unwrap_or(0),
+            max_value.iter().max().copied().unwrap_or(0)
         ),
     )
 }
 
-fn validate_ramp_h(frame: &[u8], swapped: bool, cols: usize, rows: usize) -> (bool, String) {
-    let expected_bytes = rows.saturating_mul(cols).saturating_mul(2);
-    if cols == 0 || rows == 0 {
-        return (
-            false,
-            format!("invalid geometry rows={} cols={}", rows, cols),
-        );
-    }
-    if frame.len() < expected_bytes {
-        return (
-            false,
-            format!(
-                "payload too small for geometry: got {} bytes, need at least {}",
-                frame.len(),
-                expected_bytes
-            ),
-        );
+fn validate_ramp_h(frame: &[u8], format: PixelLayout, cols: usize, rows: usize) -> (bool, String) {
+    if let Err(details) = check_geometry(frame, format, cols, rows) {
+        return (false, details);
     }
 
     let sample_rows = rows.min(24);
     let mut pass = 0usize;
     for i in 0..sample_rows {
         let row = i * rows / sample_rows;
-        let first = pixel_at(frame, cols, row, 0, swapped);
-        let last = pixel_at(frame, cols, row, cols - 1, swapped);
-        if last > first {
+        let first = pixel_at(frame, format, cols, row, 0);
+        let last = pixel_at(frame, format, cols, row, cols - 1);
+        if format.ramps(first, last) {
             pass += 1;
         }
     }
@@ -589,32 +1437,18 @@ fn validate_ramp_h(frame: &[u8], swapped: bool, cols: usize, rows: usize) -> (bo
     )
 }
 
-fn validate_ramp_v(frame: &[u8], swapped: bool, cols: usize, rows: usize) -> (bool, String) {
-    let expected_bytes = rows.saturating_mul(cols).saturating_mul(2);
-    if cols == 0 || rows == 0 {
-        return (
-            false,
-            format!("invalid geometry rows={} cols={}", rows, cols),
-        );
-    }
-    if frame.len() < expected_bytes {
-        return (
-            false,
-            format!(
-                "payload too small for geometry: got {} bytes, need at least {}",
-                frame.len(),
-                expected_bytes
-            ),
-        );
+fn validate_ramp_v(frame: &[u8], format: PixelLayout, cols: usize, rows: usize) -> (bool, String) {
+    if let Err(details) = check_geometry(frame, format, cols, rows) {
+        return (false, details);
     }
 
     let sample_cols = cols.min(24);
     let mut pass = 0usize;
     for i in 0..sample_cols {
         let col = i * cols / sample_cols;
-        let top = pixel_at(frame, cols, 0, col, swapped);
-        let bottom = pixel_at(frame, cols, rows - 1, col, swapped);
-        if bottom > top {
+        let top = pixel_at(frame, format, cols, 0, col);
+        let bottom = pixel_at(frame, format, cols, rows - 1, col);
+        if format.ramps(top, bottom) {
             pass += 1;
         }
     }
@@ -638,35 +1472,17 @@ fn validate_ramp_v(frame: &[u8], swapped: bool, cols: usize, rows: usize) -> (bo
 fn validate_pattern(
     frame: &[u8],
     source: VideoOutputSource,
+    format: PixelLayout,
     cols: usize,
     rows: usize,
 ) -> (bool, String) {
     match source {
-        VideoOutputSource::Constant => validate_constant(frame, false, cols, rows),
-        VideoOutputSource::RampH => validate_ramp_h(frame, false, cols, rows),
-        VideoOutputSource::RampV => validate_ramp_v(frame, false, cols, rows),
+        VideoOutputSource::Constant => validate_constant(frame, format, cols, rows),
+        VideoOutputSource::RampH => validate_ramp_h(frame, format, cols, rows),
+        VideoOutputSource::RampV => validate_ramp_v(frame, format, cols, rows),
         VideoOutputSource::Ramp => {
-            let (h_ok, h_details) = validate_ramp_h(frame, false, cols, rows);
-            let (v_ok, v_details) = validate_ramp_v(frame, false, cols, rows);
-            (h_ok && v_ok, format!("{}, {}", h_details, v_details))
-        }
-        _ => (false, "unsupported pattern in check".to_string()),
-    }
-}
-
-fn validate_pattern_swapped(
-    frame: &[u8],
-    source: VideoOutputSource,
-    cols: usize,
-    rows: usize,
-) -> (bool, String) {
-    match source {
-        VideoOutputSource::Constant => validate_constant(frame, true, cols, rows),
-        VideoOutputSource::RampH => validate_ramp_h(frame, true, cols, rows),
-        VideoOutputSource::RampV => validate_ramp_v(frame, true, cols, rows),
-        VideoOutputSource::Ramp => {
-            let (h_ok, h_details) = validate_ramp_h(frame, true, cols, rows);
-            let (v_ok, v_details) = validate_ramp_v(frame, true, cols, rows);
+            let (h_ok, h_details) = validate_ramp_h(frame, format, cols, rows);
+            let (v_ok, v_details) = validate_ramp_v(frame, format, cols, rows);
             (h_ok && v_ok, format!("{}, {}", h_details, v_details))
         }
         _ => (false, "unsupported pattern in check".to_string()),
@@ -685,12 +1501,33 @@ pub enum LeptonError<I2C, SPI> {
     LineOutOfOrder,
     Timeout,
     RetryLimitExceeded,
+    Decode(DecodeError),
+    DataLength(usize),
+    /// The camera rejected a CCI command with this status code.
+    Camera(LepStatus),
+    /// Driving the CS, reset or power-down pin failed.
+    Pin,
+    /// The operation needs a reset or power-down pin that was not supplied.
+    PinNotConfigured,
+}
+
+impl<I2C, SPI> From<DecodeError> for LeptonError<I2C, SPI> {
+    fn from(value: DecodeError) -> Self {
+        LeptonError::Decode(value)
+    }
 }
 
 impl<I2C, SPI> LeptonError<I2C, SPI> {
-    fn from_capture(err: CaptureError<SPI>) -> Self {
+    fn from_port(err: PortError<SPI>) -> Self {
         match err {
-            CaptureError::Spi(e) => LeptonError::Spi(e),
+            PortError::Port(e) => LeptonError::Spi(e),
+            PortError::Cs => LeptonError::Pin,
+        }
+    }
+
+    fn from_capture(err: CaptureError<PortError<SPI>>) -> Self {
+        match err {
+            CaptureError::Spi(e) => LeptonError::from_port(e),
             CaptureError::InvalidPacket => LeptonError::InvalidPacket,
             CaptureError::SyncLost => LeptonError::SyncLost,
             CaptureError::DiscardPacketFlood => LeptonError::DiscardPacketFlood,
@@ -716,6 +1553,13 @@ impl<I2C: fmt::Debug, SPI: fmt::Debug> fmt::Display for LeptonError<I2C, SPI> {
             LeptonError::LineOutOfOrder => write!(f, "Line out of order"),
             LeptonError::Timeout => write!(f, "Capture timeout"),
             LeptonError::RetryLimitExceeded => write!(f, "Capture retry limit exceeded"),
+            LeptonError::Decode(e) => write!(f, "CCI decode error: {}", e),
+            LeptonError::DataLength(words) => {
+                write!(f, "CCI payload of {} words exceeds the block buffer", words)
+            }
+            LeptonError::Camera(status) => write!(f, "Camera returned status: {}", status),
+            LeptonError::Pin => write!(f, "Control pin error"),
+            LeptonError::PinNotConfigured => write!(f, "Control pin not configured"),
         }
     }
 }
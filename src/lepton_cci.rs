@@ -1,38 +1,187 @@
+use crate::agc::{AgcHeqScale, AgcHistogramStats, AgcPolicy, AgcRoi};
 use crate::lepton_command::LepCommand;
 use crate::lepton_status::LepStatus;
+use crate::oem::{
+    GpioMode, PartNumber, ShutterProfile, SoftwareRevision, UserDefaultsState, VideoOutputFormat,
+    VideoOutputSource,
+};
+use crate::rad::{SpotmeterRoi, SpotmeterValue, TLinearResolution, TShutterMode};
+use crate::sys::{
+    CentiKelvin, CustomerSerialNumber, FfcShutterMode, FfcState, FramesToAverage, GainMode,
+    GainModeObject, SceneRoi, SceneStatistics, SerialNumber, ShutterPosition, SysCameraStatus,
+    SysRunStatus, TelemetryLocation, Uptime,
+};
+use crate::vid::{FocusRoi, Polarity, PseudoColorLut, UserLut};
 use embedded_hal::i2c::I2c;
 
 const CCI_STATUS_INTERFACE_BUSY_BIT: u16 = 1 << 0;
 const CCI_STATUS_BOOTED_BIT: u16 = 1 << 2;
-const COMMAND_POLL_TIMEOUT_MS: u16 = 1000;
+const CCI_DATA_REGISTER_COUNT: usize = 16;
+/// Size of block data buffer 0, used for payloads that do not fit in `DATA0..DATA15`.
+const CCI_BLOCK_BUFFER_WORDS: usize = 512;
 
 macro_rules! generate_get_set_functions {
     (
         $set_fn_name:ident, $get_fn_name:ident, $param_ty:ty, $set_command:expr, $get_command:expr
     ) => {
         pub fn $set_fn_name(&mut self, value: $param_ty) -> Result<LepStatus, CciError<E>> {
-            self.write_register(Register::CCIDataReg0, &value.to_be_bytes())?;
             let command = $set_command;
-            self.write_command(command)?;
-            self.poll_status()?;
-            self.get_status_code()
+            // Two-word commands carry 32-bit enums; the upper word is always zero.
+            let words = [value as u16, 0];
+            let data_words = command.data_words();
+            self.set_command_data(command, &words[..data_words])
         }
 
         pub fn $get_fn_name(&mut self) -> Result<($param_ty, LepStatus), CciError<E>> {
             let command = $get_command;
-            self.write_command(command)?;
-            self.poll_status()?;
-            let data = self.read_register(Register::CCIDataReg0)?;
-            let status_code = self.get_status_code()?;
-            Ok((data as $param_ty, status_code))
+            let mut words = [0u16; 2];
+            let data_words = command.data_words();
+            let status_code = self.get_command_data(command, &mut words[..data_words])?;
+            Ok((words[0] as $param_ty, status_code))
         }
     };
 }
 
+macro_rules! generate_typed_get_set_functions {
+    (
+        $set_fn_name:ident, $get_fn_name:ident, $param_ty:ty, $set_command:expr, $get_command:expr
+    ) => {
+        pub fn $set_fn_name(&mut self, value: $param_ty) -> Result<LepStatus, CciError<E>> {
+            self.set_words(&value, $set_command)
+        }
+
+        generate_typed_get_function!($get_fn_name, $param_ty, $get_command);
+    };
+}
+
+macro_rules! generate_typed_get_function {
+    ($get_fn_name:ident, $param_ty:ty, $get_command:expr) => {
+        pub fn $get_fn_name(&mut self) -> Result<($param_ty, LepStatus), CciError<E>> {
+            self.get_words($get_command)
+        }
+    };
+}
+
+/// Implements [`CciWords`] for a two-word enum through its `TryFrom<u32>`/`Into<u32>` pair.
+macro_rules! cci_words_via_u32 {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl $crate::lepton_cci::CciWords for $ty {
+                const WORDS: usize = 2;
+
+                fn from_words(words: &[u16]) -> Result<Self, $crate::lepton_cci::DecodeError> {
+                    <$ty>::try_from($crate::lepton_cci::words_to_u32(words))
+                }
+
+                fn to_words(&self, words: &mut [u16]) {
+                    $crate::lepton_cci::CciWords::to_words(&u32::from(*self), words);
+                }
+            }
+        )+
+    };
+}
+pub(crate) use cci_words_via_u32;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CciError<E> {
     I2c(E),
     Timeout,
+    Decode(DecodeError),
+    /// Payload longer than the block data buffer.
+    DataLength(usize),
+    /// The camera completed the command with a non-OK status code.
+    Camera(LepStatus),
+}
+
+/// The camera reported a value that has no matching variant in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeError {
+    pub type_name: &'static str,
+    pub value: u32,
+}
+
+impl DecodeError {
+    pub fn new(type_name: &'static str, value: u32) -> Self {
+        DecodeError { type_name, value }
+    }
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "unknown {} value {:#X}", self.type_name, self.value)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A value carried in the CCI data registers as a fixed number of 16-bit words.
+///
+/// Each word is sent big-endian on the wire, but 32- and 64-bit quantities are split
+/// least-significant word first (`DATA0` holds the low word), matching the FLIR SDK layout.
+pub trait CciWords: Sized {
+    const WORDS: usize;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError>;
+    fn to_words(&self, words: &mut [u16]);
+}
+
+impl CciWords for u16 {
+    const WORDS: usize = 1;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(words[0])
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = *self;
+    }
+}
+
+impl CciWords for u32 {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(words_to_u32(words))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = *self as u16;
+        words[1] = (*self >> 16) as u16;
+    }
+}
+
+/// Signed 32-bit value, e.g. the OEM VSYNC phase delay (`-3..=3`).
+impl CciWords for i32 {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(words_to_u32(words) as i32)
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        (*self as u32).to_words(words);
+    }
+}
+
+/// Two-word `LEP_*_ENABLE_E` flag: `0` is disabled, `1` is enabled.
+impl CciWords for bool {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        match words_to_u32(words) {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(DecodeError::new("bool", value)),
+        }
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        (*self as u32).to_words(words);
+    }
+}
+
+pub(crate) fn words_to_u32(words: &[u16]) -> u32 {
+    (words[0] as u32) | ((words[1] as u32) << 16)
 }
 
 impl<E> From<E> for CciError<E> {
@@ -46,6 +195,7 @@ pub struct LEPTONCCI<I2C, D> {
     i2c: I2C,
     delay: D,
     address: u8,
+    raise_status_errors: bool,
 }
 
 impl<I2C, D, E> LEPTONCCI<I2C, D>
@@ -63,9 +213,22 @@ where
             i2c,
             delay,
             address: 0x2a,
+            raise_status_errors: true,
         })
     }
 
+    /// Chooses whether non-OK camera status codes are returned as [`CciError::Camera`].
+    ///
+    /// Enabled by default. When disabled, commands return `Ok` with the raw status code and
+    /// the caller is responsible for checking it.
+    pub fn set_raise_status_errors(&mut self, enabled: bool) {
+        self.raise_status_errors = enabled;
+    }
+
+    pub fn raise_status_errors(&self) -> bool {
+        self.raise_status_errors
+    }
+
     pub fn get_boot_status(&mut self) -> Result<bool, CciError<E>> {
         let response = self.read_register(Register::CCIStatus)?;
         // Camera has booted when CCI status bit 2 is set.
@@ -78,12 +241,54 @@ where
         Ok((response & CCI_STATUS_INTERFACE_BUSY_BIT) == 0)
     }
 
+    /// Reads the status code of the last command as-is, regardless of
+    /// [`set_raise_status_errors`](Self::set_raise_status_errors).
     pub fn get_status_code(&mut self) -> Result<LepStatus, CciError<E>> {
         let response = self.read_register(Register::CCIStatus)?;
         let status = (response >> 8) as u8;
         Ok(LepStatus::from(status as i8))
     }
 
+    /// Issues a Run command, optionally preceded by input data, and waits for it to finish.
+    ///
+    /// Completion is polled for up to [`LepCommand::poll_timeout_ms`]. Because a Run command
+    /// may restart the camera, I2C errors while polling are retried until that deadline, and
+    /// the command only completes once the camera reports booted and idle. Returns the final
+    /// status code from the CCI status register.
+    pub fn run_command(
+        &mut self,
+        command: LepCommand,
+        data: Option<&[u16]>,
+    ) -> Result<LepStatus, CciError<E>> {
+        let data_words = match data {
+            Some(words) => {
+                self.write_data_words(words)?;
+                words.len()
+            }
+            None => 0,
+        };
+        let timeout_ms = command.poll_timeout_ms();
+        self.write_command(command, data_words)?;
+
+        let mut last_error = CciError::Timeout;
+        for _ in 0..timeout_ms {
+            match self.read_register(Register::CCIStatus) {
+                Ok(status)
+                    if status & CCI_STATUS_INTERFACE_BUSY_BIT == 0
+                        && status & CCI_STATUS_BOOTED_BIT != 0 =>
+                {
+                    return self.command_status();
+                }
+                Ok(_) => last_error = CciError::Timeout,
+                Err(err) => last_error = err,
+            }
+
+            self.delay.delay_ms(1);
+        }
+
+        Err(last_error)
+    }
+
     //AGC
 
     generate_get_set_functions!(
@@ -94,30 +299,379 @@ where
         LepCommand::get_agc_enable()
     );
 
+    generate_typed_get_set_functions!(
+        set_agc_policy,
+        get_agc_policy,
+        AgcPolicy,
+        LepCommand::set_agc_policy(),
+        LepCommand::get_agc_policy()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_roi,
+        get_agc_roi,
+        AgcRoi,
+        LepCommand::set_agc_roi(),
+        LepCommand::get_agc_roi()
+    );
+
+    generate_typed_get_function!(
+        get_agc_histogram_statistics,
+        AgcHistogramStats,
+        LepCommand::get_agc_histogram_statistics()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_calc_enable,
+        get_agc_calc_enable,
+        bool,
+        LepCommand::set_agc_calc_enable(),
+        LepCommand::get_agc_calc_enable()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_linear_dampening_factor,
+        get_agc_linear_dampening_factor,
+        u16,
+        LepCommand::set_agc_linear_dampening_factor(),
+        LepCommand::get_agc_linear_dampening_factor()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_dampening_factor,
+        get_agc_heq_dampening_factor,
+        u16,
+        LepCommand::set_agc_heq_dampening_factor(),
+        LepCommand::get_agc_heq_dampening_factor()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_clip_limit_high,
+        get_agc_heq_clip_limit_high,
+        u16,
+        LepCommand::set_agc_heq_clip_limit_high(),
+        LepCommand::get_agc_heq_clip_limit_high()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_clip_limit_low,
+        get_agc_heq_clip_limit_low,
+        u16,
+        LepCommand::set_agc_heq_clip_limit_low(),
+        LepCommand::get_agc_heq_clip_limit_low()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_empty_counts,
+        get_agc_heq_empty_counts,
+        u16,
+        LepCommand::set_agc_heq_empty_counts(),
+        LepCommand::get_agc_heq_empty_counts()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_scale_factor,
+        get_agc_heq_scale_factor,
+        AgcHeqScale,
+        LepCommand::set_agc_heq_scale_factor(),
+        LepCommand::get_agc_heq_scale_factor()
+    );
+
+    generate_typed_get_set_functions!(
+        set_agc_heq_linear_percent,
+        get_agc_heq_linear_percent,
+        u16,
+        LepCommand::set_agc_heq_linear_percent(),
+        LepCommand::get_agc_heq_linear_percent()
+    );
+
     //SYS
 
-    generate_get_set_functions!(
+    pub fn run_sys_ping(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_sys_ping(), None)
+    }
+
+    pub fn run_sys_ffc(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_sys_ffc(), None)
+    }
+
+    pub fn run_sys_frame_average(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_sys_frame_average(), None)
+    }
+
+    generate_typed_get_function!(
+        get_sys_camera_status,
+        SysCameraStatus,
+        LepCommand::get_sys_cam_status()
+    );
+
+    generate_typed_get_function!(
+        get_sys_serial_number,
+        SerialNumber,
+        LepCommand::get_sys_flir_serial_number()
+    );
+
+    generate_typed_get_function!(
+        get_sys_customer_serial_number,
+        CustomerSerialNumber,
+        LepCommand::get_sys_customer_serial_number()
+    );
+
+    generate_typed_get_function!(get_sys_uptime, Uptime, LepCommand::get_sys_cam_uptime());
+
+    generate_typed_get_function!(
+        get_sys_aux_temperature,
+        CentiKelvin,
+        LepCommand::get_sys_aux_temperature_kelvin()
+    );
+
+    generate_typed_get_function!(
+        get_sys_fpa_temperature,
+        CentiKelvin,
+        LepCommand::get_sys_fpa_temperature_kelvin()
+    );
+
+    generate_typed_get_set_functions!(
         set_telemetry_mode,
         get_telemetry_mode,
-        u16,
+        bool,
         LepCommand::set_sys_telemetry_mode(),
         LepCommand::get_sys_telemetry_mode()
     );
 
+    generate_typed_get_set_functions!(
+        set_sys_telemetry_location,
+        get_sys_telemetry_location,
+        TelemetryLocation,
+        LepCommand::set_sys_telemetry_location(),
+        LepCommand::get_sys_telemetry_location()
+    );
+
+    generate_typed_get_set_functions!(
+        set_sys_frames_to_average,
+        get_sys_frames_to_average,
+        FramesToAverage,
+        LepCommand::set_sys_num_frames_to_average(),
+        LepCommand::get_sys_num_frames_to_average()
+    );
+
+    generate_typed_get_function!(
+        get_sys_scene_statistics,
+        SceneStatistics,
+        LepCommand::get_sys_scene_statistics()
+    );
+
+    generate_typed_get_set_functions!(
+        set_sys_scene_roi,
+        get_sys_scene_roi,
+        SceneRoi,
+        LepCommand::set_sys_scene_roi(),
+        LepCommand::get_sys_scene_roi()
+    );
+
+    generate_typed_get_function!(
+        get_sys_thermal_shutdown_count,
+        u16,
+        LepCommand::get_sys_thermal_shutdown_count()
+    );
+
+    generate_typed_get_set_functions!(
+        set_sys_shutter_position,
+        get_sys_shutter_position,
+        ShutterPosition,
+        LepCommand::set_sys_shutter_position(),
+        LepCommand::get_sys_shutter_position()
+    );
+
+    generate_typed_get_set_functions!(
+        set_sys_ffc_shutter_mode,
+        get_sys_ffc_shutter_mode,
+        FfcShutterMode,
+        LepCommand::set_sys_ffc_shutter_mode(),
+        LepCommand::get_sys_ffc_shutter_mode()
+    );
+
+    generate_typed_get_function!(
+        get_sys_ffc_status,
+        SysRunStatus,
+        LepCommand::get_sys_ffc_status()
+    );
+
+    generate_typed_get_set_functions!(
+        set_sys_gain_mode,
+        get_sys_gain_mode,
+        GainMode,
+        LepCommand::set_sys_gain_mode(),
+        LepCommand::get_sys_gain_mode()
+    );
+
+    generate_typed_get_function!(get_sys_ffc_state, FfcState, LepCommand::get_sys_ffc_state());
+
+    generate_typed_get_set_functions!(
+        set_sys_gain_mode_object,
+        get_sys_gain_mode_object,
+        GainModeObject,
+        LepCommand::set_sys_gain_mode_object(),
+        LepCommand::get_sys_gain_mode_object()
+    );
+
+    //VID
+
+    generate_typed_get_set_functions!(
+        set_vid_polarity,
+        get_vid_polarity,
+        Polarity,
+        LepCommand::set_vid_polarity(),
+        LepCommand::get_vid_polarity()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_lut,
+        get_vid_lut,
+        PseudoColorLut,
+        LepCommand::set_vid_lut_select(),
+        LepCommand::get_vid_lut_select()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_user_lut,
+        get_vid_user_lut,
+        UserLut,
+        LepCommand::set_vid_user_lut(),
+        LepCommand::get_vid_user_lut()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_focus_calc_enable,
+        get_vid_focus_calc_enable,
+        bool,
+        LepCommand::set_vid_focus_calc_enable(),
+        LepCommand::get_vid_focus_calc_enable()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_focus_roi,
+        get_vid_focus_roi,
+        FocusRoi,
+        LepCommand::set_vid_focus_roi(),
+        LepCommand::get_vid_focus_roi()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_focus_threshold,
+        get_vid_focus_threshold,
+        u32,
+        LepCommand::set_vid_focus_threshold(),
+        LepCommand::get_vid_focus_threshold()
+    );
+
+    generate_typed_get_function!(
+        get_vid_focus_metric,
+        u32,
+        LepCommand::get_vid_focus_metric()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_sbnuc_enable,
+        get_vid_sbnuc_enable,
+        bool,
+        LepCommand::set_vid_sbnuc_enable(),
+        LepCommand::get_vid_sbnuc_enable()
+    );
+
+    generate_typed_get_set_functions!(
+        set_vid_freeze,
+        get_vid_freeze,
+        bool,
+        LepCommand::set_vid_freeze(),
+        LepCommand::get_vid_freeze()
+    );
+
     //OEM
 
-    generate_get_set_functions!(
+    /// Issues OEM Power Down. The CCI stops responding, so the command is not polled.
+    ///
+    /// The camera wakes again on a PWR_DWN_L or RESET_L transition.
+    pub fn run_oem_power_down(&mut self) -> Result<(), CciError<E>> {
+        self.write_command(LepCommand::run_oem_power_down(), 0)
+    }
+
+    /// Issues OEM Camera Reboot and waits until the camera reports booted and idle again.
+    pub fn run_oem_reboot(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_oem_reboot(), None)
+    }
+
+    /// Copies the current settings to OTP so they are restored at every power-up.
+    pub fn run_oem_save_user_defaults(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_oem_save_user_defaults(), None)
+    }
+
+    /// Restores the settings previously saved with
+    /// [`run_oem_save_user_defaults`](Self::run_oem_save_user_defaults).
+    pub fn run_oem_restore_user_defaults(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_oem_restore_user_defaults(), None)
+    }
+
+    generate_typed_get_function!(
+        get_oem_flir_part_number,
+        PartNumber,
+        LepCommand::get_oem_flir_part_number()
+    );
+
+    generate_typed_get_function!(
+        get_oem_customer_part_number,
+        PartNumber,
+        LepCommand::get_oem_customer_part_number()
+    );
+
+    generate_typed_get_function!(
+        get_oem_software_revision,
+        SoftwareRevision,
+        LepCommand::get_oem_software_revision()
+    );
+
+    generate_typed_get_set_functions!(
+        set_oem_video_output_enable,
+        get_oem_video_output_enable,
+        bool,
+        LepCommand::set_oem_video_output_enable(),
+        LepCommand::get_oem_video_output_enable()
+    );
+
+    generate_typed_get_function!(
+        get_oem_user_defaults_state,
+        UserDefaultsState,
+        LepCommand::get_oem_user_defaults_state()
+    );
+
+    generate_typed_get_set_functions!(
+        set_oem_shutter_profile,
+        get_oem_shutter_profile,
+        ShutterProfile,
+        LepCommand::set_oem_shutter_profile(),
+        LepCommand::get_oem_shutter_profile()
+    );
+
+    generate_typed_get_set_functions!(
+        set_oem_thermal_shutdown_enable,
+        get_oem_thermal_shutdown_enable,
+        bool,
+        LepCommand::set_oem_thermal_shutdown_enable(),
+        LepCommand::get_oem_thermal_shutdown_enable()
+    );
+
+    generate_typed_get_set_functions!(
         set_oem_video_output_format,
         get_oem_video_output_format,
-        u16,
+        VideoOutputFormat,
         LepCommand::set_oem_video_output_format(),
         LepCommand::get_oem_video_output_format()
     );
 
-    generate_get_set_functions!(
+    generate_typed_get_set_functions!(
         set_oem_video_output_source,
         get_oem_video_output_source,
-        u16,
+        VideoOutputSource,
         LepCommand::set_oem_video_output_source(),
         LepCommand::get_oem_video_output_source()
     );
@@ -130,24 +684,90 @@ where
         LepCommand::get_oem_video_output_source_constant()
     );
 
-    generate_get_set_functions!(
+    generate_typed_get_set_functions!(
         set_gpio_mode,
         get_gpio_mode,
-        u16,
+        GpioMode,
         LepCommand::set_oem_gpio_mode(),
         LepCommand::get_oem_gpio_mode()
     );
 
-    generate_get_set_functions!(
+    generate_typed_get_set_functions!(
         set_phase_delay,
         get_phase_delay,
-        i16,
+        i32,
         LepCommand::set_oem_phase_delay(),
         LepCommand::get_oem_phase_delay()
     );
 
     //RAD
 
+    pub fn run_rad_ffc(&mut self) -> Result<LepStatus, CciError<E>> {
+        self.run_command(LepCommand::run_rad_ffc(), None)
+    }
+
+    generate_typed_get_set_functions!(
+        set_rad_enable,
+        get_rad_enable,
+        bool,
+        LepCommand::set_rad_enable(),
+        LepCommand::get_rad_enable()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_tshutter_mode,
+        get_rad_tshutter_mode,
+        TShutterMode,
+        LepCommand::set_rad_tshutter_mode(),
+        LepCommand::get_rad_tshutter_mode()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_tshutter,
+        get_rad_tshutter,
+        CentiKelvin,
+        LepCommand::set_rad_tshutter(),
+        LepCommand::get_rad_tshutter()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_tlinear_enable,
+        get_rad_tlinear_enable,
+        bool,
+        LepCommand::set_rad_tlinear_enable(),
+        LepCommand::get_rad_tlinear_enable()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_tlinear_resolution,
+        get_rad_tlinear_resolution,
+        TLinearResolution,
+        LepCommand::set_rad_tlinear_resolution(),
+        LepCommand::get_rad_tlinear_resolution()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_tlinear_auto_resolution,
+        get_rad_tlinear_auto_resolution,
+        bool,
+        LepCommand::set_rad_tlinear_auto_resolution(),
+        LepCommand::get_rad_tlinear_auto_resolution()
+    );
+
+    generate_typed_get_set_functions!(
+        set_rad_spotmeter_roi,
+        get_rad_spotmeter_roi,
+        SpotmeterRoi,
+        LepCommand::set_rad_spotmeter_roi(),
+        LepCommand::get_rad_spotmeter_roi()
+    );
+
+    generate_typed_get_function!(
+        get_rad_spotmeter_value,
+        SpotmeterValue,
+        LepCommand::get_rad_spotmeter_value()
+    );
+
     /// Writes into a register
     #[allow(unused)]
     fn write_register(&mut self, register: Register, payload: &[u8]) -> Result<(), CciError<E>> {
@@ -157,14 +777,136 @@ where
         write_vec.extend_from_slice(&address);
         write_vec.extend_from_slice(payload);
         // i2c write
-        self.i2c.write(self.address as u8, &write_vec)?;
+        self.i2c.write(self.address, &write_vec)?;
         Ok(())
     }
 
-    //Write a command
-    fn write_command(&mut self, command: LepCommand) -> Result<(), CciError<E>> {
+    /// Writes `words` into the CCI data area.
+    ///
+    /// Up to 16 words go to `DATA0..DATA15`; longer payloads (user LUTs, large OEM
+    /// structures) go to block data buffer 0 at `0xF800`.
+    pub fn write_data_words(&mut self, words: &[u16]) -> Result<(), CciError<E>> {
+        let register = Self::data_register_for(words.len())?;
+        let mut payload = std::vec::Vec::with_capacity(words.len() * 2);
+        for word in words {
+            payload.extend_from_slice(&word.to_be_bytes());
+        }
+        self.write_register(register, &payload)
+    }
+
+    /// Reads `words.len()` words from the CCI data area, using the same register selection
+    /// as [`write_data_words`](Self::write_data_words).
+    pub fn read_data_words(&mut self, words: &mut [u16]) -> Result<(), CciError<E>> {
+        let register = Self::data_register_for(words.len())?;
+        // Only block-buffer payloads are read through the heap.
+        let mut small = [0u8; CCI_DATA_REGISTER_COUNT * 2];
+        let mut large = std::vec::Vec::new();
+        let data = if words.len() <= CCI_DATA_REGISTER_COUNT {
+            &mut small[..words.len() * 2]
+        } else {
+            large.resize(words.len() * 2, 0);
+            &mut large[..]
+        };
+        self.i2c
+            .write_read(self.address, &register.address().to_be_bytes(), data)?;
+        for (word, chunk) in words.iter_mut().zip(data.chunks_exact(2)) {
+            *word = u16::from_be_bytes([chunk[0], chunk[1]]);
+        }
+        Ok(())
+    }
+
+    /// Issues a Get command and reads `words.len()` words of response data.
+    ///
+    /// The data length register is set from `words.len()`. The data registers are not read
+    /// when the command fails with [`CciError::Camera`].
+    pub fn get_command_data(
+        &mut self,
+        command: LepCommand,
+        words: &mut [u16],
+    ) -> Result<LepStatus, CciError<E>> {
+        let timeout_ms = command.poll_timeout_ms();
+        self.write_command(command, words.len())?;
+        self.poll_status(timeout_ms)?;
+        let status_code = self.command_status()?;
+        self.read_data_words(words)?;
+        Ok(status_code)
+    }
+
+    /// Writes `words` as the payload of a Set command and issues it.
+    ///
+    /// The data length register is set from `words.len()`.
+    pub fn set_command_data(
+        &mut self,
+        command: LepCommand,
+        words: &[u16],
+    ) -> Result<LepStatus, CciError<E>> {
+        self.write_data_words(words)?;
+        let timeout_ms = command.poll_timeout_ms();
+        self.write_command(command, words.len())?;
+        self.poll_status(timeout_ms)?;
+        self.command_status()
+    }
+
+    //Status code of the command that just finished, raised as an error unless disabled
+    fn command_status(&mut self) -> Result<LepStatus, CciError<E>> {
+        let status_code = self.get_status_code()?;
+        if self.raise_status_errors && !status_code.is_ok() {
+            return Err(CciError::Camera(status_code));
+        }
+        Ok(status_code)
+    }
+
+    fn data_register_for(words: usize) -> Result<Register, CciError<E>> {
+        if words <= CCI_DATA_REGISTER_COUNT {
+            Ok(Register::CCIDataReg0)
+        } else if words <= CCI_BLOCK_BUFFER_WORDS {
+            Ok(Register::CCIBlockData)
+        } else {
+            Err(CciError::DataLength(words))
+        }
+    }
+
+    //`T::WORDS` words of scratch: on the stack when they fit the data registers, else heap
+    fn words_buffer<'a, T: CciWords>(
+        small: &'a mut [u16; CCI_DATA_REGISTER_COUNT],
+        large: &'a mut std::vec::Vec<u16>,
+    ) -> &'a mut [u16] {
+        if T::WORDS <= CCI_DATA_REGISTER_COUNT {
+            &mut small[..T::WORDS]
+        } else {
+            large.resize(T::WORDS, 0);
+            large
+        }
+    }
+
+    fn set_words<T: CciWords>(
+        &mut self,
+        value: &T,
+        command: LepCommand,
+    ) -> Result<LepStatus, CciError<E>> {
+        let mut small = [0u16; CCI_DATA_REGISTER_COUNT];
+        let mut large = std::vec::Vec::new();
+        let words = Self::words_buffer::<T>(&mut small, &mut large);
+        value.to_words(words);
+        self.set_command_data(command, words)
+    }
+
+    fn get_words<T: CciWords>(
+        &mut self,
+        command: LepCommand,
+    ) -> Result<(T, LepStatus), CciError<E>> {
+        let mut small = [0u16; CCI_DATA_REGISTER_COUNT];
+        let mut large = std::vec::Vec::new();
+        let words = Self::words_buffer::<T>(&mut small, &mut large);
+        let status_code = self.get_command_data(command, words)?;
+        let value = T::from_words(words).map_err(CciError::Decode)?;
+        Ok((value, status_code))
+    }
+
+    //Write a command, with the data length taken from the payload actually transferred
+    fn write_command(&mut self, command: LepCommand, data_words: usize) -> Result<(), CciError<E>> {
         let command_id = command.get_command_id();
-        let data_length = command.get_data_length();
+        let data_length = (data_words as u16).to_be_bytes();
         self.write_register(Register::CCIDataLength, &data_length)?;
         self.write_register(Register::CCICommandID, &command_id)
     }
@@ -174,16 +916,13 @@ where
         // Buffer for values
         let mut data: [u8; 2] = [0; 2];
         // i2c write_read
-        self.i2c.write_read(
-            self.address as u8,
-            &register.address().to_be_bytes(),
-            &mut data,
-        )?;
+        self.i2c
+            .write_read(self.address, &register.address().to_be_bytes(), &mut data)?;
         Ok(u16::from_be_bytes(data))
     }
 
-    fn poll_status(&mut self) -> Result<(), CciError<E>> {
-        for _ in 0..COMMAND_POLL_TIMEOUT_MS {
+    fn poll_status(&mut self, timeout_ms: u32) -> Result<(), CciError<E>> {
+        for _ in 0..timeout_ms {
             let command_finished = self.get_interface_status()?;
             if command_finished {
                 return Ok(());
@@ -203,6 +942,22 @@ pub enum Register {
     CCICommandID = 0x0004,
     CCIDataLength = 0x0006,
     CCIDataReg0 = 0x0008,
+    CCIDataReg1 = 0x000A,
+    CCIDataReg2 = 0x000C,
+    CCIDataReg3 = 0x000E,
+    CCIDataReg4 = 0x0010,
+    CCIDataReg5 = 0x0012,
+    CCIDataReg6 = 0x0014,
+    CCIDataReg7 = 0x0016,
+    CCIDataReg8 = 0x0018,
+    CCIDataReg9 = 0x001A,
+    CCIDataReg10 = 0x001C,
+    CCIDataReg11 = 0x001E,
+    CCIDataReg12 = 0x0020,
+    CCIDataReg13 = 0x0022,
+    CCIDataReg14 = 0x0024,
+    CCIDataReg15 = 0x0026,
+    CCIBlockData = 0xF800,
 }
 
 impl Register {
@@ -210,3 +965,174 @@ impl Register {
         *self as u16
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+    use std::collections::HashMap;
+
+    /// Auto-incrementing 16-bit register file; the status register always reads booted/idle.
+    #[derive(Default)]
+    struct MockCci {
+        registers: HashMap<u16, u16>,
+        /// Number of upcoming status reads that NACK, as while the camera reboots.
+        status_nacks: u32,
+        /// Status code reported in the high byte of the status register.
+        status_code: i8,
+    }
+
+    impl ErrorType for MockCci {
+        type Error = ErrorKind;
+    }
+
+    impl I2c for MockCci {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            let mut pointer = 0u16;
+            for operation in operations {
+                match operation {
+                    Operation::Write(bytes) => {
+                        pointer = u16::from_be_bytes([bytes[0], bytes[1]]);
+                        for chunk in bytes[2..].chunks_exact(2) {
+                            self.registers
+                                .insert(pointer, u16::from_be_bytes([chunk[0], chunk[1]]));
+                            pointer += 2;
+                        }
+                    }
+                    Operation::Read(buffer) => {
+                        if pointer == Register::CCIStatus.address() && self.status_nacks > 0 {
+                            self.status_nacks -= 1;
+                            return Err(ErrorKind::Other);
+                        }
+                        for chunk in buffer.chunks_exact_mut(2) {
+                            let value = if pointer == Register::CCIStatus.address() {
+                                CCI_STATUS_BOOTED_BIT | ((self.status_code as u8 as u16) << 8)
+                            } else {
+                                self.registers.get(&pointer).copied().unwrap_or(0)
+                            };
+                            chunk.copy_from_slice(&value.to_be_bytes());
+                            pointer += 2;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+
+    impl embedded_hal::delay::DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn cci() -> LEPTONCCI<MockCci, NoDelay> {
+        LEPTONCCI::new(MockCci::default(), NoDelay).unwrap()
+    }
+
+    #[test]
+    fn short_payload_uses_data_registers_and_real_length() {
+        let mut cci = cci();
+        cci.set_command_data(LepCommand::set_agc_roi(), &[1, 2, 3, 4])
+            .unwrap();
+
+        let registers = &cci.i2c.registers;
+        assert_eq!(registers[&Register::CCIDataLength.address()], 4);
+        assert_eq!(registers[&Register::CCIDataReg0.address()], 1);
+        assert_eq!(registers[&Register::CCIDataReg3.address()], 4);
+    }
+
+    #[test]
+    fn long_payload_uses_block_buffer() {
+        let mut cci = cci();
+        let words: Vec<u16> = (0..40).collect();
+        cci.set_command_data(LepCommand::set_vid_user_lut(), &words)
+            .unwrap();
+
+        let registers = &cci.i2c.registers;
+        assert_eq!(registers[&Register::CCIDataLength.address()], 40);
+        assert_eq!(registers[&(Register::CCIBlockData.address() + 2 * 39)], 39);
+        assert!(!registers.contains_key(&Register::CCIDataReg0.address()));
+
+        let mut readback = vec![0u16; 40];
+        cci.read_data_words(&mut readback).unwrap();
+        assert_eq!(readback, words);
+    }
+
+    #[test]
+    fn oversized_payload_is_rejected() {
+        let mut cci = cci();
+        let words = vec![0u16; CCI_BLOCK_BUFFER_WORDS + 1];
+        assert!(matches!(
+            cci.write_data_words(&words),
+            Err(CciError::DataLength(513))
+        ));
+    }
+
+    #[test]
+    fn single_word_getter_pads_enum_commands_to_two_words() {
+        let mut cci = cci();
+        cci.set_agc_enable(1).unwrap();
+
+        let registers = &cci.i2c.registers;
+        assert_eq!(registers[&Register::CCIDataLength.address()], 2);
+        assert_eq!(registers[&Register::CCIDataReg1.address()], 0);
+        assert_eq!(cci.get_agc_enable().unwrap().0, 1);
+    }
+
+    #[test]
+    fn negative_phase_delay_is_sign_extended() {
+        let mut cci = cci();
+        cci.set_phase_delay(-2).unwrap();
+
+        let registers = &cci.i2c.registers;
+        assert_eq!(registers[&Register::CCIDataLength.address()], 2);
+        assert_eq!(registers[&Register::CCIDataReg0.address()], 0xFFFE);
+        assert_eq!(registers[&Register::CCIDataReg1.address()], 0xFFFF);
+        assert_eq!(cci.get_phase_delay().unwrap().0, -2);
+    }
+
+    #[test]
+    fn run_command_writes_input_data_and_length() {
+        let mut cci = cci();
+        let status = cci
+            .run_command(LepCommand::run_sys_ffc(), Some(&[7, 8]))
+            .unwrap();
+
+        assert!(matches!(status, LepStatus::OK));
+        let registers = &cci.i2c.registers;
+        assert_eq!(registers[&Register::CCIDataLength.address()], 2);
+        assert_eq!(registers[&Register::CCICommandID.address()], 0x0242);
+        assert_eq!(registers[&Register::CCIDataReg1.address()], 8);
+    }
+
+    #[test]
+    fn run_command_retries_nacks_while_camera_restarts() {
+        let mut cci = cci();
+        cci.i2c.status_nacks = 50;
+        assert!(cci.run_oem_reboot().is_ok());
+
+        cci.i2c.status_nacks = u32::MAX;
+        assert!(matches!(
+            cci.run_command(LepCommand::run_sys_ping(), None),
+            Err(CciError::I2c(ErrorKind::Other))
+        ));
+    }
+
+    #[test]
+    fn non_ok_status_is_raised_unless_disabled() {
+        let mut cci = cci();
+        cci.i2c.status_code = LepStatus::RangeError.into();
+        assert_eq!(
+            cci.set_agc_enable(1),
+            Err(CciError::Camera(LepStatus::RangeError))
+        );
+
+        cci.set_raise_status_errors(false);
+        assert_eq!(cci.set_agc_enable(1), Ok(LepStatus::RangeError));
+    }
+}
@@ -33,14 +33,18 @@ impl std::ops::Add<u16> for Module {
 
 #[cfg(test)]
 impl LepCommand {
-    fn raw_command_id(&self) -> u16 {
+    pub(crate) fn raw_command_id(&self) -> u16 {
         self.command_id
     }
 }
 
+/// Default time allowed for the CCI busy bit to clear after issuing a command.
+const DEFAULT_POLL_TIMEOUT_MS: u32 = 1000;
+
 pub struct LepCommand {
     command_id: u16,
     data_length: u16,
+    poll_timeout_ms: u32,
 }
 
 macro_rules! lep_command_fn {
@@ -49,6 +53,20 @@ macro_rules! lep_command_fn {
             LepCommand::new($module, $command_type, $base_id, $data_length)
         }
     };
+    (
+        $fn_name:ident,
+        $module:expr,
+        $command_type:expr,
+        $base_id:expr,
+        $data_length:expr,
+        $poll_timeout_ms:expr
+    ) => {
+        pub fn $fn_name() -> LepCommand {
+            let mut command = LepCommand::new($module, $command_type, $base_id, $data_length);
+            command.poll_timeout_ms = $poll_timeout_ms;
+            command
+        }
+    };
 }
 
 #[allow(unused)]
@@ -65,6 +83,7 @@ impl LepCommand {
         LepCommand {
             command_id,
             data_length,
+            poll_timeout_ms: DEFAULT_POLL_TIMEOUT_MS,
         }
     }
 
@@ -76,6 +95,18 @@ impl LepCommand {
         self.data_length.to_be_bytes()
     }
 
+    /// Number of 16-bit data words the IDD specifies for this command.
+    pub fn data_words(&self) -> usize {
+        self.data_length as usize
+    }
+
+    /// How long to wait for the command to complete before reporting a timeout.
+    ///
+    /// Run commands that move the shutter or restart the camera get longer budgets.
+    pub fn poll_timeout_ms(&self) -> u32 {
+        self.poll_timeout_ms
+    }
+
     lep_command_fn!(set_agc_enable, Module::AGC, CommandType::Set, 0x00, 2);
     lep_command_fn!(get_agc_enable, Module::AGC, CommandType::Get, 0x00, 2);
     lep_command_fn!(set_agc_policy, Module::AGC, CommandType::Set, 0x04, 2);
@@ -89,23 +120,251 @@ impl LepCommand {
         0x0C,
         4
     );
-    lep_command_fn!(set_oem_phase_delay, Module::OEM, CommandType::Set, 0x58, 1);
-    lep_command_fn!(get_oem_phase_delay, Module::OEM, CommandType::Get, 0x58, 1);
-    lep_command_fn!(set_oem_gpio_mode, Module::OEM, CommandType::Set, 0x54, 1);
-    lep_command_fn!(get_oem_gpio_mode, Module::OEM, CommandType::Get, 0x54, 1);
+    lep_command_fn!(
+        set_agc_linear_dampening_factor,
+        Module::AGC,
+        CommandType::Set,
+        0x20,
+        1
+    );
+    lep_command_fn!(
+        get_agc_linear_dampening_factor,
+        Module::AGC,
+        CommandType::Get,
+        0x20,
+        1
+    );
+    lep_command_fn!(
+        set_agc_heq_dampening_factor,
+        Module::AGC,
+        CommandType::Set,
+        0x24,
+        1
+    );
+    lep_command_fn!(
+        get_agc_heq_dampening_factor,
+        Module::AGC,
+        CommandType::Get,
+        0x24,
+        1
+    );
+    lep_command_fn!(
+        set_agc_heq_clip_limit_high,
+        Module::AGC,
+        CommandType::Set,
+        0x2C,
+        1
+    );
+    lep_command_fn!(
+        get_agc_heq_clip_limit_high,
+        Module::AGC,
+        CommandType::Get,
+        0x2C,
+        1
+    );
+    lep_command_fn!(
+        set_agc_heq_clip_limit_low,
+        Module::AGC,
+        CommandType::Set,
+        0x30,
+        1
+    );
+    lep_command_fn!(
+        get_agc_heq_clip_limit_low,
+        Module::AGC,
+        CommandType::Get,
+        0x30,
+        1
+    );
+    lep_command_fn!(
+        set_agc_heq_empty_counts,
+        Module::AGC,
+        CommandType::Set,
+        0x3C,
+        1
+    );
+    lep_command_fn!(
+        get_agc_heq_empty_counts,
+        Module::AGC,
+        CommandType::Get,
+        0x3C,
+        1
+    );
+    lep_command_fn!(
+        set_agc_heq_scale_factor,
+        Module::AGC,
+        CommandType::Set,
+        0x44,
+        2
+    );
+    lep_command_fn!(
+        get_agc_heq_scale_factor,
+        Module::AGC,
+        CommandType::Get,
+        0x44,
+        2
+    );
+    lep_command_fn!(set_agc_calc_enable, Module::AGC, CommandType::Set, 0x48, 2);
+    lep_command_fn!(get_agc_calc_enable, Module::AGC, CommandType::Get, 0x48, 2);
+    lep_command_fn!(
+        set_agc_heq_linear_percent,
+        Module::AGC,
+        CommandType::Set,
+        0x4C,
+        1
+    );
+    lep_command_fn!(
+        get_agc_heq_linear_percent,
+        Module::AGC,
+        CommandType::Get,
+        0x4C,
+        1
+    );
+    lep_command_fn!(set_vid_polarity, Module::VID, CommandType::Set, 0x00, 2);
+    lep_command_fn!(get_vid_polarity, Module::VID, CommandType::Get, 0x00, 2);
+    lep_command_fn!(set_vid_lut_select, Module::VID, CommandType::Set, 0x04, 2);
+    lep_command_fn!(get_vid_lut_select, Module::VID, CommandType::Get, 0x04, 2);
+    lep_command_fn!(set_vid_user_lut, Module::VID, CommandType::Set, 0x08, 512);
+    lep_command_fn!(get_vid_user_lut, Module::VID, CommandType::Get, 0x08, 512);
+    lep_command_fn!(
+        set_vid_focus_calc_enable,
+        Module::VID,
+        CommandType::Set,
+        0x0C,
+        2
+    );
+    lep_command_fn!(
+        get_vid_focus_calc_enable,
+        Module::VID,
+        CommandType::Get,
+        0x0C,
+        2
+    );
+    lep_command_fn!(set_vid_focus_roi, Module::VID, CommandType::Set, 0x10, 4);
+    lep_command_fn!(get_vid_focus_roi, Module::VID, CommandType::Get, 0x10, 4);
+    lep_command_fn!(
+        set_vid_focus_threshold,
+        Module::VID,
+        CommandType::Set,
+        0x14,
+        2
+    );
+    lep_command_fn!(
+        get_vid_focus_threshold,
+        Module::VID,
+        CommandType::Get,
+        0x14,
+        2
+    );
+    lep_command_fn!(get_vid_focus_metric, Module::VID, CommandType::Get, 0x18, 2);
+    lep_command_fn!(set_vid_sbnuc_enable, Module::VID, CommandType::Set, 0x1C, 2);
+    lep_command_fn!(get_vid_sbnuc_enable, Module::VID, CommandType::Get, 0x1C, 2);
+    lep_command_fn!(set_vid_freeze, Module::VID, CommandType::Set, 0x24, 2);
+    lep_command_fn!(get_vid_freeze, Module::VID, CommandType::Get, 0x24, 2);
+    lep_command_fn!(run_oem_power_down, Module::OEM, CommandType::Run, 0x00, 0);
+    lep_command_fn!(
+        get_oem_flir_part_number,
+        Module::OEM,
+        CommandType::Get,
+        0x1C,
+        16
+    );
+    lep_command_fn!(
+        get_oem_software_revision,
+        Module::OEM,
+        CommandType::Get,
+        0x20,
+        4
+    );
+    lep_command_fn!(
+        set_oem_video_output_enable,
+        Module::OEM,
+        CommandType::Set,
+        0x24,
+        2
+    );
+    lep_command_fn!(
+        get_oem_video_output_enable,
+        Module::OEM,
+        CommandType::Get,
+        0x24,
+        2
+    );
+    lep_command_fn!(
+        get_oem_customer_part_number,
+        Module::OEM,
+        CommandType::Get,
+        0x38,
+        16
+    );
+    lep_command_fn!(run_oem_reboot, Module::OEM, CommandType::Run, 0x40, 0, 6000);
+    lep_command_fn!(
+        get_oem_user_defaults_state,
+        Module::OEM,
+        CommandType::Get,
+        0x5C,
+        2
+    );
+    lep_command_fn!(
+        run_oem_save_user_defaults,
+        Module::OEM,
+        CommandType::Run,
+        0x5C,
+        0,
+        3000
+    );
+    lep_command_fn!(
+        run_oem_restore_user_defaults,
+        Module::OEM,
+        CommandType::Run,
+        0x60,
+        0
+    );
+    lep_command_fn!(
+        set_oem_shutter_profile,
+        Module::OEM,
+        CommandType::Set,
+        0x64,
+        2
+    );
+    lep_command_fn!(
+        get_oem_shutter_profile,
+        Module::OEM,
+        CommandType::Get,
+        0x64,
+        2
+    );
+    lep_command_fn!(
+        set_oem_thermal_shutdown_enable,
+        Module::OEM,
+        CommandType::Set,
+        0x68,
+        2
+    );
+    lep_command_fn!(
+        get_oem_thermal_shutdown_enable,
+        Module::OEM,
+        CommandType::Get,
+        0x68,
+        2
+    );
+    lep_command_fn!(set_oem_phase_delay, Module::OEM, CommandType::Set, 0x58, 2);
+    lep_command_fn!(get_oem_phase_delay, Module::OEM, CommandType::Get, 0x58, 2);
+    lep_command_fn!(set_oem_gpio_mode, Module::OEM, CommandType::Set, 0x54, 2);
+    lep_command_fn!(get_oem_gpio_mode, Module::OEM, CommandType::Get, 0x54, 2);
     lep_command_fn!(
         set_oem_video_output_source,
         Module::OEM,
         CommandType::Set,
         0x2C,
-        1
+        2
     );
     lep_command_fn!(
         get_oem_video_output_source,
         Module::OEM,
         CommandType::Get,
         0x2C,
-        1
+        2
     );
     lep_command_fn!(
         set_oem_video_output_source_constant,
@@ -121,33 +380,244 @@ impl LepCommand {
         0x3C,
         1
     );
+    lep_command_fn!(run_sys_ping, Module::SYS, CommandType::Run, 0x00, 0);
+    lep_command_fn!(get_sys_cam_status, Module::SYS, CommandType::Get, 0x04, 4);
+    lep_command_fn!(
+        get_sys_flir_serial_number,
+        Module::SYS,
+        CommandType::Get,
+        0x08,
+        4
+    );
+    lep_command_fn!(get_sys_cam_uptime, Module::SYS, CommandType::Get, 0x0C, 2);
+    lep_command_fn!(
+        get_sys_aux_temperature_kelvin,
+        Module::SYS,
+        CommandType::Get,
+        0x10,
+        1
+    );
+    lep_command_fn!(
+        get_sys_fpa_temperature_kelvin,
+        Module::SYS,
+        CommandType::Get,
+        0x14,
+        1
+    );
     lep_command_fn!(
         set_sys_telemetry_mode,
         Module::SYS,
         CommandType::Set,
         0x18,
-        1
+        2
     );
     lep_command_fn!(
         get_sys_telemetry_mode,
         Module::SYS,
         CommandType::Get,
         0x18,
+        2
+    );
+    lep_command_fn!(
+        set_sys_telemetry_location,
+        Module::SYS,
+        CommandType::Set,
+        0x1C,
+        2
+    );
+    lep_command_fn!(
+        get_sys_telemetry_location,
+        Module::SYS,
+        CommandType::Get,
+        0x1C,
+        2
+    );
+    lep_command_fn!(
+        run_sys_frame_average,
+        Module::SYS,
+        CommandType::Run,
+        0x20,
+        0
+    );
+    lep_command_fn!(
+        set_sys_num_frames_to_average,
+        Module::SYS,
+        CommandType::Set,
+        0x24,
+        2
+    );
+    lep_command_fn!(
+        get_sys_num_frames_to_average,
+        Module::SYS,
+        CommandType::Get,
+        0x24,
+        2
+    );
+    lep_command_fn!(
+        get_sys_customer_serial_number,
+        Module::SYS,
+        CommandType::Get,
+        0x28,
+        16
+    );
+    lep_command_fn!(
+        get_sys_scene_statistics,
+        Module::SYS,
+        CommandType::Get,
+        0x2C,
+        4
+    );
+    lep_command_fn!(set_sys_scene_roi, Module::SYS, CommandType::Set, 0x30, 4);
+    lep_command_fn!(get_sys_scene_roi, Module::SYS, CommandType::Get, 0x30, 4);
+    lep_command_fn!(
+        get_sys_thermal_shutdown_count,
+        Module::SYS,
+        CommandType::Get,
+        0x34,
         1
     );
+    lep_command_fn!(
+        set_sys_shutter_position,
+        Module::SYS,
+        CommandType::Set,
+        0x38,
+        2
+    );
+    lep_command_fn!(
+        get_sys_shutter_position,
+        Module::SYS,
+        CommandType::Get,
+        0x38,
+        2
+    );
+    lep_command_fn!(
+        set_sys_ffc_shutter_mode,
+        Module::SYS,
+        CommandType::Set,
+        0x3C,
+        16
+    );
+    lep_command_fn!(
+        get_sys_ffc_shutter_mode,
+        Module::SYS,
+        CommandType::Get,
+        0x3C,
+        16
+    );
+    lep_command_fn!(run_sys_ffc, Module::SYS, CommandType::Run, 0x40, 0, 5000);
+    lep_command_fn!(get_sys_ffc_status, Module::SYS, CommandType::Get, 0x44, 2);
+    lep_command_fn!(set_sys_gain_mode, Module::SYS, CommandType::Set, 0x48, 2);
+    lep_command_fn!(get_sys_gain_mode, Module::SYS, CommandType::Get, 0x48, 2);
+    lep_command_fn!(get_sys_ffc_state, Module::SYS, CommandType::Get, 0x4C, 2);
+    lep_command_fn!(
+        set_sys_gain_mode_object,
+        Module::SYS,
+        CommandType::Set,
+        0x50,
+        14
+    );
+    lep_command_fn!(
+        get_sys_gain_mode_object,
+        Module::SYS,
+        CommandType::Get,
+        0x50,
+        14
+    );
+    lep_command_fn!(run_rad_ffc, Module::RAD, CommandType::Run, 0x2C, 0, 5000);
+    lep_command_fn!(set_rad_enable, Module::RAD, CommandType::Set, 0x10, 2);
+    lep_command_fn!(get_rad_enable, Module::RAD, CommandType::Get, 0x10, 2);
+    lep_command_fn!(
+        set_rad_tshutter_mode,
+        Module::RAD,
+        CommandType::Set,
+        0x24,
+        2
+    );
+    lep_command_fn!(
+        get_rad_tshutter_mode,
+        Module::RAD,
+        CommandType::Get,
+        0x24,
+        2
+    );
+    lep_command_fn!(set_rad_tshutter, Module::RAD, CommandType::Set, 0x28, 1);
+    lep_command_fn!(get_rad_tshutter, Module::RAD, CommandType::Get, 0x28, 1);
+    lep_command_fn!(
+        set_rad_tlinear_enable,
+        Module::RAD,
+        CommandType::Set,
+        0xC0,
+        2
+    );
+    lep_command_fn!(
+        get_rad_tlinear_enable,
+        Module::RAD,
+        CommandType::Get,
+        0xC0,
+        2
+    );
+    lep_command_fn!(
+        set_rad_tlinear_resolution,
+        Module::RAD,
+        CommandType::Set,
+        0xC4,
+        2
+    );
+    lep_command_fn!(
+        get_rad_tlinear_resolution,
+        Module::RAD,
+        CommandType::Get,
+        0xC4,
+        2
+    );
+    lep_command_fn!(
+        set_rad_tlinear_auto_resolution,
+        Module::RAD,
+        CommandType::Set,
+        0xC8,
+        2
+    );
+    lep_command_fn!(
+        get_rad_tlinear_auto_resolution,
+        Module::RAD,
+        CommandType::Get,
+        0xC8,
+        2
+    );
+    lep_command_fn!(
+        set_rad_spotmeter_roi,
+        Module::RAD,
+        CommandType::Set,
+        0xCC,
+        4
+    );
+    lep_command_fn!(
+        get_rad_spotmeter_roi,
+        Module::RAD,
+        CommandType::Get,
+        0xCC,
+        4
+    );
+    lep_command_fn!(
+        get_rad_spotmeter_value,
+        Module::RAD,
+        CommandType::Get,
+        0xD0,
+        4
+    );
     lep_command_fn!(
         set_oem_video_output_format,
         Module::OEM,
         CommandType::Set,
         0x28,
-        1
+        2
     );
     lep_command_fn!(
         get_oem_video_output_format,
         Module::OEM,
         CommandType::Get,
         0x28,
-        1
+        2
     );
 }
 
@@ -183,4 +653,27 @@ mod tests {
             assert_eq!(command_id & COMMAND_TYPE_MASK, GET_COMMAND_TYPE);
         }
     }
+
+    #[test]
+    fn oem_run_commands_use_run_command_type() {
+        assert_eq!(LepCommand::run_oem_power_down().raw_command_id(), 0x4802);
+        assert_eq!(LepCommand::run_oem_reboot().raw_command_id(), 0x4842);
+        assert_eq!(
+            LepCommand::run_oem_save_user_defaults().raw_command_id(),
+            0x485E
+        );
+        assert_eq!(
+            LepCommand::run_oem_restore_user_defaults().raw_command_id(),
+            0x4862
+        );
+    }
+
+    #[test]
+    fn ffc_and_reboot_get_longer_poll_timeouts() {
+        let default_timeout = LepCommand::get_agc_enable().poll_timeout_ms();
+        assert!(LepCommand::run_sys_ffc().poll_timeout_ms() > default_timeout);
+        assert!(LepCommand::run_rad_ffc().poll_timeout_ms() > default_timeout);
+        assert!(LepCommand::run_oem_reboot().poll_timeout_ms() > default_timeout);
+        assert_eq!(LepCommand::run_rad_ffc().raw_command_id(), 0x4E2E);
+    }
 }
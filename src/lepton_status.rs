@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LepStatus {
     OK,
     CommOK,
@@ -48,6 +48,36 @@ pub enum LepStatus {
     UndefinedErrorCode,
 }
 
+impl LepStatus {
+    /// True for the success codes (`LEP_OK` and `LEP_COMM_OK`).
+    pub fn is_ok(&self) -> bool {
+        matches!(self, LepStatus::OK | LepStatus::CommOK)
+    }
+
+    /// True for transient failures where repeating the same command may succeed.
+    ///
+    /// Covers the camera being busy or not ready, and corrupted or interrupted transfers;
+    /// argument, range and permission errors will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            LepStatus::NotReady
+                | LepStatus::ChecksumError
+                | LepStatus::I2CBusNotReady
+                | LepStatus::I2CBufferOverflow
+                | LepStatus::I2CArbitrationLost
+                | LepStatus::I2CBusError
+                | LepStatus::I2CNackReceived
+                | LepStatus::I2CFail
+                | LepStatus::CommChecksumError
+                | LepStatus::TimeoutError
+                | LepStatus::CommErrorWritingComm
+                | LepStatus::CommErrorReadingComm
+                | LepStatus::CommCountError
+        )
+    }
+}
+
 impl From<i8> for LepStatus {
     fn from(value: i8) -> Self {
         match value {
@@ -91,9 +121,9 @@ impl From<i8> for LepStatus {
     }
 }
 
-impl Into<i8> for LepStatus {
-    fn into(self) -> i8 {
-        match self {
+impl From<LepStatus> for i8 {
+    fn from(value: LepStatus) -> i8 {
+        match value {
             LepStatus::OK => 0,
             LepStatus::CommOK => 0,
             LepStatus::Error => -1,
@@ -192,3 +222,16 @@ impl std::error::Error for LepStatus {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_classify() {
+        assert!(LepStatus::from(0).is_ok());
+        assert!(!LepStatus::from(-3).is_ok());
+        assert!(LepStatus::from(-2).is_retryable());
+        assert!(!LepStatus::from(-11).is_retryable());
+    }
+}
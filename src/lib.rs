@@ -1,7 +1,17 @@
+pub mod agc;
 pub mod crc;
 pub mod lepton;
 pub mod lepton_cci;
 pub mod lepton_command;
 pub mod lepton_status;
 pub mod oem;
+pub mod rad;
+pub mod record;
+pub mod recovery;
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
+pub mod sys;
+pub mod telemetry;
+pub mod vid;
 pub mod vospi;
+pub mod vsync;
@@ -1,3 +1,5 @@
+use crate::lepton_cci::{CciWords, DecodeError};
+
 /// FLIR Lepton OEM module video output source selector.
 ///
 /// The values map to the OEM CCI command pair at module `0x0800`, command base
@@ -15,3 +17,307 @@ pub enum VideoOutputSource {
     RampH = 4,
     RampV = 5,
 }
+
+impl TryFrom<u16> for VideoOutputSource {
+    type Error = DecodeError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VideoOutputSource::Raw),
+            1 => Ok(VideoOutputSource::Cooked),
+            2 => Ok(VideoOutputSource::Ramp),
+            3 => Ok(VideoOutputSource::Constant),
+            4 => Ok(VideoOutputSource::RampH),
+            5 => Ok(VideoOutputSource::RampV),
+            _ => Err(DecodeError::new("VideoOutputSource", value as u32)),
+        }
+    }
+}
+
+/// Pixel format of the VoSPI stream (`0x4828`).
+///
+/// Only `Raw14` and `Rgb888` are produced by current Lepton firmware; `Rgb888` applies
+/// AGC and the selected pseudo-color LUT.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VideoOutputFormat {
+    Raw8 = 0,
+    Raw10 = 1,
+    Raw12 = 2,
+    Rgb888 = 3,
+    Rgb666 = 4,
+    Rgb565 = 5,
+    Yuv422 = 6,
+    #[default]
+    Raw14 = 7,
+    Raw16 = 8,
+    UserDefined = 9,
+}
+
+impl VideoOutputFormat {
+    /// Bytes per pixel in the VoSPI payload: 3 for `Rgb888`, otherwise 2.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            VideoOutputFormat::Rgb888 => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl TryFrom<u16> for VideoOutputFormat {
+    type Error = DecodeError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VideoOutputFormat::Raw8),
+            1 => Ok(VideoOutputFormat::Raw10),
+            2 => Ok(VideoOutputFormat::Raw12),
+            3 => Ok(VideoOutputFormat::Rgb888),
+            4 => Ok(VideoOutputFormat::Rgb666),
+            5 => Ok(VideoOutputFormat::Rgb565),
+            6 => Ok(VideoOutputFormat::Yuv422),
+            7 => Ok(VideoOutputFormat::Raw14),
+            8 => Ok(VideoOutputFormat::Raw16),
+            9 => Ok(VideoOutputFormat::UserDefined),
+            _ => Err(DecodeError::new("VideoOutputFormat", value as u32)),
+        }
+    }
+}
+
+/// Function of the GPIO3 pin (`0x4854`).
+///
+/// `Vsync` drives a frame-ready pulse that can replace polling for the next segment.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpioMode {
+    Gpio = 0,
+    I2cMaster = 1,
+    SpiMasterVlbData = 2,
+    SpiMasterRegData = 3,
+    SpiSlaveVlbData = 4,
+    Vsync = 5,
+}
+
+impl TryFrom<u16> for GpioMode {
+    type Error = DecodeError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(GpioMode::Gpio),
+            1 => Ok(GpioMode::I2cMaster),
+            2 => Ok(GpioMode::SpiMasterVlbData),
+            3 => Ok(GpioMode::SpiMasterRegData),
+            4 => Ok(GpioMode::SpiSlaveVlbData),
+            5 => Ok(GpioMode::Vsync),
+            _ => Err(DecodeError::new("GpioMode", value as u32)),
+        }
+    }
+}
+
+// These are 32-bit enums over CCI; values that do not fit in 16 bits are rejected too.
+macro_rules! u32_via_u16 {
+    ($($ty:ident),+) => {
+        $(
+            impl TryFrom<u32> for $ty {
+                type Error = DecodeError;
+
+                fn try_from(value: u32) -> Result<Self, Self::Error> {
+                    u16::try_from(value)
+                        .map_err(|_| DecodeError::new(stringify!($ty), value))
+                        .and_then($ty::try_from)
+                }
+            }
+
+            impl From<$ty> for u32 {
+                fn from(value: $ty) -> Self {
+                    value as u32
+                }
+            }
+        )+
+    };
+}
+
+u32_via_u16!(VideoOutputSource, VideoOutputFormat, GpioMode);
+
+crate::lepton_cci::cci_words_via_u32!(VideoOutputSource, VideoOutputFormat, GpioMode);
+
+/// FLIR or customer part number (`0x481C` / `0x4838`), 32 ASCII bytes padded with NULs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartNumber(pub [u8; 32]);
+
+impl PartNumber {
+    /// Part number text with trailing NUL padding removed.
+    ///
+    /// Returns `None` if the camera reported bytes that are not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        let end = self.0.iter().position(|&b| b == 0).unwrap_or(self.0.len());
+        core::str::from_utf8(&self.0[..end]).ok()
+    }
+}
+
+impl CciWords for PartNumber {
+    const WORDS: usize = 16;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(2).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(PartNumber(bytes))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        for (word, chunk) in words.iter_mut().zip(self.0.chunks_exact(2)) {
+            *word = u16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+    }
+}
+
+/// Camera software revision (`0x4820`): GPP (host processor) and DSP firmware versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoftwareRevision {
+    pub gpp_major: u8,
+    pub gpp_minor: u8,
+    pub gpp_build: u8,
+    pub dsp_major: u8,
+    pub dsp_minor: u8,
+    pub dsp_build: u8,
+}
+
+impl CciWords for SoftwareRevision {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        let [gpp_major, gpp_minor] = words[0].to_le_bytes();
+        let [gpp_build, dsp_major] = words[1].to_le_bytes();
+        let [dsp_minor, dsp_build] = words[2].to_le_bytes();
+        Ok(SoftwareRevision {
+            gpp_major,
+            gpp_minor,
+            gpp_build,
+            dsp_major,
+            dsp_minor,
+            dsp_build,
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = u16::from_le_bytes([self.gpp_major, self.gpp_minor]);
+        words[1] = u16::from_le_bytes([self.gpp_build, self.dsp_major]);
+        words[2] = u16::from_le_bytes([self.dsp_minor, self.dsp_build]);
+        words[3] = 0;
+    }
+}
+
+impl core::fmt::Display for SoftwareRevision {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "GPP {}.{}.{} / DSP {}.{}.{}",
+            self.gpp_major,
+            self.gpp_minor,
+            self.gpp_build,
+            self.dsp_major,
+            self.dsp_minor,
+            self.dsp_build
+        )
+    }
+}
+
+/// Whether user defaults have been written to OTP (`0x485C`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDefaultsState {
+    NotWritten = 0,
+    Written = 1,
+}
+
+impl TryFrom<u32> for UserDefaultsState {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(UserDefaultsState::NotWritten),
+            1 => Ok(UserDefaultsState::Written),
+            _ => Err(DecodeError::new("UserDefaultsState", value)),
+        }
+    }
+}
+
+impl From<UserDefaultsState> for u32 {
+    fn from(value: UserDefaultsState) -> Self {
+        value as u32
+    }
+}
+
+crate::lepton_cci::cci_words_via_u32!(UserDefaultsState);
+
+/// Shutter close/open timing used during FFC (`0x4864`), in frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShutterProfile {
+    pub close_period_in_frames: u16,
+    pub open_period_in_frames: u16,
+}
+
+impl CciWords for ShutterProfile {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(ShutterProfile {
+            close_period_in_frames: words[0],
+            open_period_in_frames: words[1],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.close_period_in_frames;
+        words[1] = self.open_period_in_frames;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_number_strips_nul_padding() {
+        let mut words = [0u16; 16];
+        // "500-0771" in SDK memory order.
+        words[0] = u16::from_le_bytes(*b"50");
+        words[1] = u16::from_le_bytes(*b"0-");
+        words[2] = u16::from_le_bytes(*b"07");
+        words[3] = u16::from_le_bytes(*b"71");
+        let part = PartNumber::from_words(&words).unwrap();
+        assert_eq!(part.as_str(), Some("500-0771"));
+    }
+
+    #[test]
+    fn software_revision_unpacks_bytes() {
+        let revision = SoftwareRevision::from_words(&[0x0303, 0x031A, 0x1A03, 0]).unwrap();
+        assert_eq!(revision.to_string(), "GPP 3.3.26 / DSP 3.3.26");
+    }
+
+    #[test]
+    fn unknown_enum_values_are_rejected() {
+        assert_eq!(GpioMode::try_from(5u16), Ok(GpioMode::Vsync));
+        assert_eq!(
+            VideoOutputFormat::try_from(10u16),
+            Err(DecodeError::new("VideoOutputFormat", 10))
+        );
+        assert!(VideoOutputSource::try_from(6u16).is_err());
+    }
+
+    #[test]
+    fn upper_word_of_32_bit_enums_is_checked() {
+        assert_eq!(GpioMode::from_words(&[5, 0]), Ok(GpioMode::Vsync));
+        assert_eq!(
+            VideoOutputFormat::from_words(&[7, 1]),
+            Err(DecodeError::new("VideoOutputFormat", 0x0001_0007))
+        );
+        assert!(VideoOutputSource::from_words(&[0, 0x8000]).is_err());
+
+        let mut words = [0xFFFF; 2];
+        VideoOutputSource::RampV.to_words(&mut words);
+        assert_eq!(words, [5, 0]);
+    }
+}
@@ -0,0 +1,191 @@
+//! Typed values for the FLIR Lepton RAD (radiometry) module (`0x0E00`).
+//!
+//! Radiometric commands are only meaningful on radiometric parts such as the Lepton 3.5.
+//! See Lepton Software IDD Rev 303, RAD module.
+
+use crate::lepton_cci::{CciWords, DecodeError};
+
+/// Source of the shutter temperature used by the radiometric pipeline (`0x4E24`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TShutterMode {
+    /// Use the value written with `set_rad_tshutter`.
+    User = 0,
+    /// Use the camera's calibrated shutter temperature model.
+    Cal = 1,
+    Fixed = 2,
+}
+
+impl TryFrom<u32> for TShutterMode {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TShutterMode::User),
+            1 => Ok(TShutterMode::Cal),
+            2 => Ok(TShutterMode::Fixed),
+            _ => Err(DecodeError::new("TShutterMode", value)),
+        }
+    }
+}
+
+impl From<TShutterMode> for u32 {
+    fn from(value: TShutterMode) -> Self {
+        value as u32
+    }
+}
+
+/// Scale of TLinear pixel values (`0x4EC4`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TLinearResolution {
+    /// Each count is 0.1 K.
+    Resolution0_1 = 0,
+    /// Each count is 0.01 K.
+    Resolution0_01 = 1,
+}
+
+impl TLinearResolution {
+    /// Kelvin represented by a single TLinear count.
+    pub fn kelvin_per_count(self) -> f32 {
+        match self {
+            TLinearResolution::Resolution0_1 => 0.1,
+            TLinearResolution::Resolution0_01 => 0.01,
+        }
+    }
+
+    /// Converts a 16-bit TLinear pixel (or spotmeter value) to Kelvin.
+    pub fn to_kelvin(self, raw: u16) -> f32 {
+        raw as f32 * self.kelvin_per_count()
+    }
+}
+
+impl TryFrom<u32> for TLinearResolution {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TLinearResolution::Resolution0_1),
+            1 => Ok(TLinearResolution::Resolution0_01),
+            _ => Err(DecodeError::new("TLinearResolution", value)),
+        }
+    }
+}
+
+impl From<TLinearResolution> for u32 {
+    fn from(value: TLinearResolution) -> Self {
+        value as u32
+    }
+}
+
+crate::lepton_cci::cci_words_via_u32!(TShutterMode, TLinearResolution);
+
+/// Spotmeter region of interest (`0x4ECC`), inclusive pixel coordinates.
+///
+/// Unlike the AGC and SYS ROIs, the RAD ROI is transferred row-first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpotmeterRoi {
+    pub start_row: u16,
+    pub start_col: u16,
+    pub end_row: u16,
+    pub end_col: u16,
+}
+
+impl CciWords for SpotmeterRoi {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(SpotmeterRoi {
+            start_row: words[0],
+            start_col: words[1],
+            end_row: words[2],
+            end_col: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.start_row;
+        words[1] = self.start_col;
+        words[2] = self.end_row;
+        words[3] = self.end_col;
+    }
+}
+
+/// Spotmeter readout (`0x4ED0`).
+///
+/// Values use the same scale as TLinear pixels, so convert them with the active
+/// [`TLinearResolution`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpotmeterValue {
+    pub value: u16,
+    pub max_value: u16,
+    pub min_value: u16,
+    pub population: u16,
+}
+
+impl CciWords for SpotmeterValue {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(SpotmeterValue {
+            value: words[0],
+            max_value: words[1],
+            min_value: words[2],
+            population: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.value;
+        words[1] = self.max_value;
+        words[2] = self.min_value;
+        words[3] = self.population;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lepton_command::LepCommand;
+
+    #[test]
+    fn rad_commands_carry_oem_bit() {
+        assert_eq!(LepCommand::get_rad_enable().raw_command_id(), 0x4E10);
+        assert_eq!(
+            LepCommand::set_rad_tlinear_enable().raw_command_id(),
+            0x4EC1
+        );
+        assert_eq!(
+            LepCommand::get_rad_tlinear_resolution().raw_command_id(),
+            0x4EC4
+        );
+        assert_eq!(LepCommand::get_rad_spotmeter_roi().raw_command_id(), 0x4ECC);
+        assert_eq!(
+            LepCommand::get_rad_spotmeter_value().raw_command_id(),
+            0x4ED0
+        );
+    }
+
+    #[test]
+    fn tlinear_resolution_scales_counts() {
+        assert_eq!(TLinearResolution::Resolution0_01.to_kelvin(30000), 300.0);
+        assert_eq!(TLinearResolution::Resolution0_1.to_kelvin(3000), 300.0);
+    }
+
+    #[test]
+    fn spotmeter_roi_is_row_first() {
+        let roi = SpotmeterRoi::from_words(&[59, 79, 60, 80]).unwrap();
+        assert_eq!(roi.start_row, 59);
+        assert_eq!(roi.start_col, 79);
+        assert_eq!(roi.end_row, 60);
+        assert_eq!(roi.end_col, 80);
+    }
+
+    #[test]
+    fn tlinear_resolution_rejects_unknown_value() {
+        assert_eq!(
+            TLinearResolution::from_words(&[2, 0]).unwrap_err(),
+            DecodeError::new("TLinearResolution", 2)
+        );
+    }
+}
@@ -0,0 +1,331 @@
+//! Recording and offline replay of raw VoSPI packets.
+//!
+//! [`RecordingSource`] wraps any [`PacketSource`] and writes every packet it returns,
+//! discard packets included, to a log together with the tick at which it was read.
+//! [`ReplaySource`] reads such a log back as a `PacketSource`, so a stream captured from a
+//! misbehaving unit can be fed through robust capture on a host.
+//!
+//! The log starts with the magic `LVSP` and a format version byte. Each packet follows as
+//! the tick delta from the previous packet and the packet length, both LEB128-encoded,
+//! then the packet bytes.
+
+use std::io::{self, Read, Write};
+
+use crate::vospi::PacketSource;
+
+const LOG_MAGIC: [u8; 4] = *b"LVSP";
+const LOG_VERSION: u8 = 1;
+/// Longest packet a log may contain (RGB888 VoSPI packets are 244 bytes).
+const MAX_PACKET_BYTES: u64 = 4096;
+
+#[derive(Debug)]
+pub enum RecordError<E> {
+    Source(E),
+    Io(io::Error),
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The log does not start with the expected magic and version.
+    BadHeader,
+    /// A record is malformed or cut off part way.
+    Corrupt,
+    /// All recorded packets have been replayed.
+    EndOfLog,
+    /// The reader asked for a packet of a different size than was recorded.
+    PacketSize {
+        recorded: usize,
+        requested: usize,
+    },
+}
+
+fn write_varint(writer: &mut impl Write, mut value: u64) -> io::Result<()> {
+    let mut bytes = [0u8; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&bytes[..len])
+}
+
+/// Reads a LEB128 value, or `None` at a clean end of the log.
+fn read_varint(reader: &mut impl Read) -> Result<Option<u64>, ReplayError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8; 1];
+        match reader.read_exact(&mut byte) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return if shift == 0 {
+                    Ok(None)
+                } else {
+                    Err(ReplayError::Corrupt)
+                };
+            }
+            Err(err) => return Err(ReplayError::Io(err)),
+        }
+        value |= u64::from(byte[0] & 0x7F) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(ReplayError::Corrupt)
+}
+
+/// Packet source that logs every packet read from `source` to `writer`.
+///
+/// Writes are not buffered here; wrap files in a `BufWriter` and call
+/// [`release`](Self::release) or [`flush`](Self::flush) when done.
+pub struct RecordingSource<S: PacketSource, W, F> {
+    source: S,
+    writer: W,
+    now_ticks: F,
+    last_ticks: u64,
+    packets: u64,
+}
+
+impl<S, W, F> RecordingSource<S, W, F>
+where
+    S: PacketSource,
+    W: Write,
+    F: FnMut() -> u64,
+{
+    /// Writes the log header; `now_ticks` timestamps each packet, in any unit.
+    pub fn new(source: S, mut writer: W, now_ticks: F) -> io::Result<Self> {
+        writer.write_all(&LOG_MAGIC)?;
+        writer.write_all(&[LOG_VERSION])?;
+        Ok(RecordingSource {
+            source,
+            writer,
+            now_ticks,
+            last_ticks: 0,
+            packets: 0,
+        })
+    }
+
+    pub fn packets_recorded(&self) -> u64 {
+        self.packets
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes the log and returns the wrapped source and writer.
+    pub fn release(mut self) -> io::Result<(S, W)> {
+        self.writer.flush()?;
+        Ok((self.source, self.writer))
+    }
+
+    fn record(&mut self, packet: &[u8]) -> io::Result<()> {
+        let ticks = (self.now_ticks)();
+        write_varint(&mut self.writer, ticks.wrapping_sub(self.last_ticks))?;
+        write_varint(&mut self.writer, packet.len() as u64)?;
+        self.writer.write_all(packet)?;
+        self.last_ticks = ticks;
+        self.packets += 1;
+        Ok(())
+    }
+}
+
+impl<S, W, F> PacketSource for RecordingSource<S, W, F>
+where
+    S: PacketSource,
+    W: Write,
+    F: FnMut() -> u64,
+{
+    type Error = RecordError<S::Error>;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        self.source
+            .read_packet(packet)
+            .map_err(RecordError::Source)?;
+        self.record(packet).map_err(RecordError::Io)
+    }
+
+    fn read_packets(&mut self, packets: &mut [u8], packet_size: usize) -> Result<(), Self::Error> {
+        self.source
+            .read_packets(packets, packet_size)
+            .map_err(RecordError::Source)?;
+        for packet in packets.chunks_exact(packet_size) {
+            self.record(packet).map_err(RecordError::Io)?;
+        }
+        Ok(())
+    }
+
+    fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+        self.source
+            .wait_segment_start()
+            .map_err(RecordError::Source)
+    }
+
+    fn resync_idle(&mut self, idle_ms: u32) -> Result<(), Self::Error> {
+        self.source
+            .resync_idle(idle_ms)
+            .map_err(RecordError::Source)
+    }
+}
+
+/// Packet source that plays back a log written by [`RecordingSource`].
+///
+/// Segment waits and resync idles are no-ops: packets are returned in recorded order
+/// regardless of what the capture logic does, as they were on the wire.
+pub struct ReplaySource<R> {
+    reader: R,
+    ticks: u64,
+    packets: u64,
+}
+
+impl<R: Read> ReplaySource<R> {
+    /// Checks the log header.
+    pub fn new(mut reader: R) -> Result<Self, ReplayError> {
+        let mut header = [0u8; LOG_MAGIC.len() + 1];
+        reader
+            .read_exact(&mut header)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => ReplayError::BadHeader,
+                _ => ReplayError::Io(err),
+            })?;
+        if header[..LOG_MAGIC.len()] != LOG_MAGIC || header[LOG_MAGIC.len()] != LOG_VERSION {
+            return Err(ReplayError::BadHeader);
+        }
+
+        Ok(ReplaySource {
+            reader,
+            ticks: 0,
+            packets: 0,
+        })
+    }
+
+    /// Recorded tick of the last packet returned.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn packets_replayed(&self) -> u64 {
+        self.packets
+    }
+
+    pub fn release(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> PacketSource for ReplaySource<R> {
+    type Error = ReplayError;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        let delta = read_varint(&mut self.reader)?.ok_or(ReplayError::EndOfLog)?;
+        let len = read_varint(&mut self.reader)?.ok_or(ReplayError::Corrupt)?;
+        if len > MAX_PACKET_BYTES {
+            return Err(ReplayError::Corrupt);
+        }
+
+        let len = len as usize;
+        if len != packet.len() {
+            return Err(ReplayError::PacketSize {
+                recorded: len,
+                requested: packet.len(),
+            });
+        }
+        self.reader
+            .read_exact(packet)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => ReplayError::Corrupt,
+                _ => ReplayError::Io(err),
+            })?;
+
+        self.ticks = self.ticks.wrapping_add(delta);
+        self.packets += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::synth::{FaultRates, FaultSchedule, FaultySource};
+    use crate::vospi::{
+        capture_frame_from_source, FrameDiagnostics, RobustCaptureConfig, SyncState,
+    };
+
+    fn capture_frames<S: PacketSource>(
+        source: &mut S,
+        cfg: &RobustCaptureConfig,
+        frames: usize,
+    ) -> (Vec<Vec<u8>>, FrameDiagnostics)
+    where
+        S::Error: std::fmt::Debug,
+    {
+        let mut synced = false;
+        let mut state = SyncState::Unsynced;
+        let mut diag = FrameDiagnostics::default();
+        let pixels = (0..frames)
+            .map(|_| {
+                capture_frame_from_source(source, cfg, &mut synced, &mut state, &mut diag, || 0)
+                    .unwrap()
+                    .pixels
+            })
+            .collect();
+        (pixels, diag)
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_capture() {
+        let cfg = RobustCaptureConfig {
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+        let schedule = FaultSchedule {
+            seed: 11,
+            rates: FaultRates {
+                discard_burst: 0.02,
+                segment_zero: 0.1,
+                ..FaultRates::default()
+            },
+            start_mid_frame: true,
+        };
+        // The tick source may borrow, e.g. a clock owned by the caller.
+        let clock = std::cell::Cell::new(0);
+        let mut recorder =
+            RecordingSource::new(FaultySource::new(&cfg, schedule), Vec::new(), || {
+                clock.set(clock.get() + 5);
+                clock.get()
+            })
+            .unwrap();
+        let (recorded, recorded_diag) = capture_frames(&mut recorder, &cfg, 3);
+        let packets = recorder.packets_recorded();
+        let (_, log) = recorder.release().unwrap();
+
+        let mut replay = ReplaySource::new(log.as_slice()).unwrap();
+        let (replayed, replayed_diag) = capture_frames(&mut replay, &cfg, 3);
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed_diag.discard_count, recorded_diag.discard_count);
+        assert_eq!(replayed_diag.resync_count, recorded_diag.resync_count);
+        assert_eq!(
+            replayed_diag.invalid_segment_count,
+            recorded_diag.invalid_segment_count
+        );
+        assert_eq!(replay.packets_replayed(), packets);
+        assert_eq!(replay.ticks(), packets * 5);
+
+        let mut packet = vec![0u8; cfg.packet_size_bytes];
+        assert!(matches!(
+            replay.read_packet(&mut packet),
+            Err(ReplayError::EndOfLog)
+        ));
+        assert!(matches!(
+            ReplaySource::new(&b"LVSP\x02"[..]),
+            Err(ReplayError::BadHeader)
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+//! Escalating recovery for a camera whose VoSPI stream can no longer be captured.
+//!
+//! When robust capture gives up on the VoSPI stream (out of retries, sync lost, a discard
+//! flood or a timeout), [`Lepton`] runs the enabled [`RecoveryStep`]s in order, retrying
+//! the capture after each, until a frame is captured or the steps run out. Bus errors are
+//! returned unchanged.
+//!
+//! [`Lepton`]: crate::lepton::Lepton
+
+/// Default wait for the camera to report booted after a reboot or reset.
+const DEFAULT_BOOT_TIMEOUT_MS: u32 = 5_000;
+
+/// One recovery action, in escalation order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryStep {
+    /// Deassert CS and idle SCK so the camera restarts its VoSPI stream.
+    IdleResync,
+    /// OEM Camera Reboot over CCI, then wait for the boot status bit.
+    CciReboot,
+    /// Pulse RESET_L, then wait for the boot status bit. Needs a reset pin on `Lepton`;
+    /// skipped without one.
+    HardwareReset,
+}
+
+/// Which recovery steps `Lepton` may take after a failed capture.
+///
+/// The default enables none, so capture errors are returned unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    pub idle_resync: bool,
+    pub cci_reboot: bool,
+    pub hardware_reset: bool,
+    /// Idle period of [`RecoveryStep::IdleResync`]; raised to at least
+    /// [`MIN_RESYNC_IDLE_MS`](crate::vospi::MIN_RESYNC_IDLE_MS).
+    pub idle_ms: u32,
+    /// How long to poll the boot status after a reboot or reset; also used by
+    /// `Lepton::hard_reset` and `Lepton::power_up`.
+    pub boot_timeout_ms: u32,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            idle_resync: false,
+            cci_reboot: false,
+            hardware_reset: false,
+            idle_ms: crate::vospi::MIN_RESYNC_IDLE_MS,
+            boot_timeout_ms: DEFAULT_BOOT_TIMEOUT_MS,
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    /// Enables every step: idle resync, then CCI reboot, then hardware reset.
+    pub fn escalating() -> Self {
+        Self {
+            idle_resync: true,
+            cci_reboot: true,
+            hardware_reset: true,
+            ..Self::default()
+        }
+    }
+
+    /// Enabled steps in escalation order.
+    pub fn steps(self) -> impl Iterator<Item = RecoveryStep> {
+        [
+            (self.idle_resync, RecoveryStep::IdleResync),
+            (self.cci_reboot, RecoveryStep::CciReboot),
+            (self.hardware_reset, RecoveryStep::HardwareReset),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, step)| enabled.then_some(step))
+    }
+}
+
+/// Cumulative recovery counters kept by `Lepton`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryCounters {
+    pub idle_resyncs: u32,
+    pub cci_reboots: u32,
+    pub hardware_resets: u32,
+    /// Steps that themselves failed, e.g. the camera did not report booted in time.
+    pub failed_steps: u32,
+    /// Captures that succeeded after at least one step.
+    pub recovered: u32,
+    /// Captures that still failed after every enabled step.
+    pub unrecovered: u32,
+}
+
+impl RecoveryCounters {
+    pub(crate) fn record(&mut self, step: RecoveryStep) {
+        match step {
+            RecoveryStep::IdleResync => self.idle_resyncs += 1,
+            RecoveryStep::CciReboot => self.cci_reboots += 1,
+            RecoveryStep::HardwareReset => self.hardware_resets += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_escalate_in_order_and_skip_disabled() {
+        assert_eq!(RecoveryPolicy::default().steps().count(), 0);
+        assert_eq!(
+            RecoveryPolicy::escalating().steps().collect::<Vec<_>>(),
+            vec![
+                RecoveryStep::IdleResync,
+                RecoveryStep::CciReboot,
+                RecoveryStep::HardwareReset
+            ]
+        );
+
+        let policy = RecoveryPolicy {
+            idle_resync: false,
+            ..RecoveryPolicy::escalating()
+        };
+        assert_eq!(policy.steps().next(), Some(RecoveryStep::CciReboot));
+    }
+}
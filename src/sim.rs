@@ -0,0 +1,1025 @@
+//! Simulated Lepton for exercising the whole driver on a host.
+//!
+//! [`VirtualLepton`] keeps the camera state behind an `Rc<RefCell<_>>` and hands out
+//! handles implementing the embedded-hal traits the driver needs: [`SimI2c`] runs the CCI
+//! register state machine (status busy/boot bits, command ID, data length, `DATA0..DATA15`
+//! and the block buffer), [`SimSpi`] streams VoSPI packets for the configured output source,
+//! format and telemetry, and [`SimPin`] models CS, RESET_L and PWR_DWN_L.
+//!
+//! [`VirtualLepton::lose_sync`] and [`VirtualLepton::hang`] put the camera in the failure
+//! states that `Lepton`'s recovery steps are meant to clear.
+//!
+//! Get/Set attributes are stored per command, so anything the driver writes reads back.
+//! Time is not modelled: commands finish after one busy status read and every SPI read
+//! returns the next packet. Available with the `sim` feature.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::i2c::{self, I2c, NoAcknowledgeSource};
+use embedded_hal::spi::{self, SpiDevice};
+
+use crate::crc::lepton_packet_crc16_spec;
+use crate::lepton_cci::Register;
+use crate::lepton_command::LepCommand;
+use crate::lepton_status::LepStatus;
+use crate::oem::{VideoOutputFormat, VideoOutputSource};
+use crate::sys::{TelemetryLocation, TelemetryMode};
+use crate::telemetry::TELEMETRY_ROW_WORDS;
+use crate::vospi::{
+    packets_per_segment, telemetry_packets_per_segment, RobustCaptureConfig, SensorModel,
+};
+
+pub mod synth;
+
+const CCI_ADDRESS: u8 = 0x2A;
+const CCI_STATUS_BUSY_BIT: u16 = 1 << 0;
+const CCI_STATUS_BOOTED_BIT: u16 = 1 << 2;
+const CCI_DATA_REGISTER_COUNT: usize = 16;
+const CCI_BLOCK_BUFFER_WORDS: usize = 512;
+/// Status reads NACKed while the camera restarts after a reboot or reset.
+const BOOT_NACK_READS: u32 = 3;
+const PIXELS_PER_PACKET: usize = 80;
+/// Telemetry time counter step, one frame at ~27 Hz.
+const FRAME_PERIOD_MS: u32 = 37;
+const FPA_TEMPERATURE_CK: u16 = 30_015;
+const HOUSING_TEMPERATURE_CK: u16 = 30_115;
+
+fn command_id(command: LepCommand) -> u16 {
+    u16::from_be_bytes(command.get_command_id())
+}
+
+/// Attribute key of a Get/Set command: its ID without the command type bits.
+fn attribute(command: LepCommand) -> u16 {
+    command_id(command) & !0x3
+}
+
+/// Shared state of one simulated camera.
+struct State {
+    model: SensorModel,
+    attributes: HashMap<u16, Vec<u16>>,
+    command_id: u16,
+    data_length: u16,
+    data: [u16; CCI_DATA_REGISTER_COUNT],
+    block: [u16; CCI_BLOCK_BUFFER_WORDS],
+    status_code: LepStatus,
+    busy_reads: u32,
+    boot_nacks: u32,
+    in_reset: bool,
+    powered_down: bool,
+    /// VoSPI sends only discard packets until CS is deasserted.
+    stalled: bool,
+    /// CCI and VoSPI are unresponsive until RESET_L or PWR_DWN_L restarts the camera.
+    hung: bool,
+    reboots: u32,
+    /// Settings of the frame being streamed are latched at its first packet.
+    stream: FrameStream,
+    packet: Vec<u8>,
+    packet_offset: usize,
+}
+
+impl State {
+    fn new(model: SensorModel) -> Self {
+        let mut state = State {
+            model,
+            attributes: HashMap::new(),
+            command_id: 0,
+            data_length: 0,
+            data: [0; CCI_DATA_REGISTER_COUNT],
+            block: [0; CCI_BLOCK_BUFFER_WORDS],
+            status_code: LepStatus::OK,
+            busy_reads: 0,
+            boot_nacks: 0,
+            in_reset: false,
+            powered_down: false,
+            stalled: false,
+            hung: false,
+            reboots: 0,
+            stream: FrameStream::new(model, RobustCaptureConfig::for_sensor(model)),
+            packet: Vec::new(),
+            packet_offset: 0,
+        };
+        state.load_defaults();
+        state
+    }
+
+    /// Power-on attribute values that the driver decodes into enums.
+    fn load_defaults(&mut self) {
+        self.attributes.clear();
+        self.attributes.insert(
+            attribute(LepCommand::get_oem_video_output_enable()),
+            vec![1, 0],
+        );
+        self.attributes.insert(
+            attribute(LepCommand::get_oem_video_output_format()),
+            vec![VideoOutputFormat::Raw14 as u16, 0],
+        );
+    }
+
+    fn reboot(&mut self) {
+        self.load_defaults();
+        self.reboots += 1;
+        self.boot_nacks = BOOT_NACK_READS;
+        self.busy_reads = 0;
+        self.status_code = LepStatus::OK;
+        self.stalled = false;
+        self.hung = false;
+        self.restart_stream();
+    }
+
+    fn restart_stream(&mut self) {
+        self.stream.restart();
+        self.packet.clear();
+        self.packet_offset = 0;
+    }
+
+    fn attribute_word(&self, command: LepCommand) -> u16 {
+        self.attributes
+            .get(&attribute(command))
+            .and_then(|words| words.first().copied())
+            .unwrap_or(0)
+    }
+
+    fn output_source(&self) -> VideoOutputSource {
+        VideoOutputSource::try_from(self.attribute_word(LepCommand::get_oem_video_output_source()))
+            .unwrap_or(VideoOutputSource::Raw)
+    }
+
+    fn output_format(&self) -> VideoOutputFormat {
+        VideoOutputFormat::try_from(self.attribute_word(LepCommand::get_oem_video_output_format()))
+            .unwrap_or_default()
+    }
+
+    fn telemetry(&self) -> TelemetryMode {
+        if self.attribute_word(LepCommand::get_sys_telemetry_mode()) == 0 {
+            return TelemetryMode::Disabled;
+        }
+        let location = TelemetryLocation::try_from(
+            self.attribute_word(LepCommand::get_sys_telemetry_location()) as u32,
+        )
+        .ok();
+        TelemetryMode::from(location.or(Some(TelemetryLocation::Header)))
+    }
+
+    // CCI
+
+    fn read_register(&mut self, address: u16) -> u16 {
+        match address {
+            a if a == Register::CCIStatus as u16 => {
+                let busy = if self.busy_reads > 0 {
+                    self.busy_reads -= 1;
+                    CCI_STATUS_BUSY_BIT
+                } else {
+                    0
+                };
+                let code = i8::from(self.status_code) as u8 as u16;
+                busy | CCI_STATUS_BOOTED_BIT | (code << 8)
+            }
+            a if a == Register::CCICommandID as u16 => self.command_id,
+            a if a == Register::CCIDataLength as u16 => self.data_length,
+            a => self.data_word(a).map(|word| *word).unwrap_or(0),
+        }
+    }
+
+    fn write_register(&mut self, address: u16, value: u16) {
+        match address {
+            a if a == Register::CCICommandID as u16 => {
+                self.command_id = value;
+                self.execute(value);
+            }
+            a if a == Register::CCIDataLength as u16 => self.data_length = value,
+            a => {
+                if let Some(word) = self.data_word(a) {
+                    *word = value;
+                }
+            }
+        }
+    }
+
+    fn data_word(&mut self, address: u16) -> Option<&mut u16> {
+        let data0 = Register::CCIDataReg0 as u16;
+        let block = Register::CCIBlockData as u16;
+        if address >= block {
+            self.block.get_mut(((address - block) / 2) as usize)
+        } else if address >= data0 {
+            self.data.get_mut(((address - data0) / 2) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Data area for a payload of `words`, as selected by the driver.
+    fn data_area(&mut self, words: usize) -> Option<&mut [u16]> {
+        if words <= CCI_DATA_REGISTER_COUNT {
+            Some(&mut self.data[..words])
+        } else if words <= CCI_BLOCK_BUFFER_WORDS {
+            Some(&mut self.block[..words])
+        } else {
+            None
+        }
+    }
+
+    fn execute(&mut self, command: u16) {
+        let key = command & !0x3;
+        let words = self.data_length as usize;
+        self.busy_reads = 1;
+
+        self.status_code = match command & 0x3 {
+            // Get
+            0 => {
+                let value = self.attributes.get(&key).cloned().unwrap_or_default();
+                match self.data_area(words) {
+                    Some(area) => {
+                        for (index, word) in area.iter_mut().enumerate() {
+                            *word = value.get(index).copied().unwrap_or(0);
+                        }
+                        LepStatus::OK
+                    }
+                    None => LepStatus::DataSizeError,
+                }
+            }
+            // Set
+            1 => match self.data_area(words) {
+                Some(area) => {
+                    let value = area.to_vec();
+                    self.attributes.insert(key, value);
+                    LepStatus::OK
+                }
+                None => LepStatus::DataSizeError,
+            },
+            // Run
+            2 => {
+                if command == command_id(LepCommand::run_oem_reboot()) {
+                    self.reboot();
+                } else if command == command_id(LepCommand::run_oem_power_down()) {
+                    self.powered_down = true;
+                }
+                LepStatus::OK
+            }
+            _ => LepStatus::UndefinedFunctionError,
+        };
+    }
+
+    // VoSPI
+
+    fn fill_spi(&mut self, buffer: &mut [u8]) {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            if self.packet_offset == self.packet.len() {
+                self.packet = self.next_packet();
+                self.packet_offset = 0;
+            }
+            let len = (self.packet.len() - self.packet_offset).min(buffer.len() - filled);
+            buffer[filled..filled + len]
+                .copy_from_slice(&self.packet[self.packet_offset..self.packet_offset + len]);
+            self.packet_offset += len;
+            filled += len;
+        }
+    }
+
+    fn next_packet(&mut self) -> Vec<u8> {
+        let video_enabled = self.attribute_word(LepCommand::get_oem_video_output_enable()) != 0;
+        if self.in_reset || self.powered_down || self.stalled || self.hung || !video_enabled {
+            return discard_packet(self.stream.cfg.packet_size_bytes);
+        }
+
+        if self.stream.at_frame_start() {
+            // Output settings take effect at the next frame.
+            self.stream.source = self.output_source();
+            self.stream.constant =
+                self.attribute_word(LepCommand::get_oem_video_output_source_constant());
+            self.stream.cfg = RobustCaptureConfig {
+                telemetry: self.telemetry(),
+                ..RobustCaptureConfig::for_sensor(self.model)
+                    .with_output_format(self.output_format())
+            };
+        }
+        self.stream.next_packet()
+    }
+}
+
+/// Error-free VoSPI packet stream for a fixed geometry and output source.
+#[derive(Debug, Clone)]
+pub(crate) struct FrameStream {
+    pub(crate) model: SensorModel,
+    pub(crate) cfg: RobustCaptureConfig,
+    pub(crate) source: VideoOutputSource,
+    pub(crate) constant: u16,
+    /// Counter of the frame being streamed, as reported in telemetry; starts at 1.
+    pub(crate) frame_counter: u32,
+    packet_index: usize,
+}
+
+impl FrameStream {
+    pub(crate) fn new(model: SensorModel, cfg: RobustCaptureConfig) -> Self {
+        FrameStream {
+            model,
+            cfg,
+            source: VideoOutputSource::Raw,
+            constant: 0,
+            frame_counter: 0,
+            packet_index: 0,
+        }
+    }
+
+    /// Position of the next packet in the frame, telemetry included.
+    pub(crate) fn packet_index(&self) -> usize {
+        self.packet_index
+    }
+
+    pub(crate) fn at_frame_start(&self) -> bool {
+        self.packet_index == 0
+    }
+
+    pub(crate) fn packets_per_frame(&self) -> usize {
+        packets_per_segment(&self.cfg) * self.cfg.segments_per_frame
+    }
+
+    pub(crate) fn restart(&mut self) {
+        self.packet_index = 0;
+    }
+
+    pub(crate) fn next_packet(&mut self) -> Vec<u8> {
+        if self.packet_index == 0 {
+            self.frame_counter = self.frame_counter.wrapping_add(1);
+        }
+
+        let cfg = self.cfg;
+        let packets_per_segment = packets_per_segment(&cfg);
+        let telemetry_packets = telemetry_packets_per_segment(&cfg) * cfg.segments_per_frame;
+        let image_packets = cfg.lines_per_segment * cfg.segments_per_frame;
+        let frame_packet = self.packet_index;
+        let segment = frame_packet / packets_per_segment + 1;
+        let packet_number = frame_packet % packets_per_segment;
+
+        let mut packet = vec![0u8; cfg.packet_size_bytes];
+        let mut id = packet_number as u16;
+        if packet_number == 20 && cfg.segments_per_frame > 1 {
+            id |= (segment as u16) << 12;
+        }
+        packet[0..2].copy_from_slice(&id.to_be_bytes());
+
+        let image_line = match cfg.telemetry {
+            TelemetryMode::Disabled => Some(frame_packet),
+            TelemetryMode::Header => frame_packet.checked_sub(telemetry_packets),
+            TelemetryMode::Footer => (frame_packet < image_packets).then_some(frame_packet),
+        };
+        match image_line {
+            Some(line) => self.fill_image_line(line, &mut packet[4..]),
+            None => {
+                let row = match cfg.telemetry {
+                    TelemetryMode::Footer => frame_packet - image_packets,
+                    _ => frame_packet,
+                };
+                if row == 0 {
+                    self.fill_telemetry_row_a(&mut packet[4..]);
+                }
+            }
+        }
+
+        set_packet_crc(&mut packet);
+        self.packet_index = (self.packet_index + 1) % self.packets_per_frame();
+        packet
+    }
+
+    /// Image bytes of frame `frame_counter`, laid out as robust capture stores them.
+    pub(crate) fn image(&self, frame_counter: u32) -> Vec<u8> {
+        let stream = FrameStream {
+            frame_counter,
+            ..self.clone()
+        };
+        let payload_len = self.cfg.packet_size_bytes - 4;
+        let lines = self.cfg.lines_per_segment * self.cfg.segments_per_frame;
+        let mut image = vec![0u8; payload_len * lines];
+        for (line, payload) in image.chunks_exact_mut(payload_len).enumerate() {
+            stream.fill_image_line(line, payload);
+        }
+        image
+    }
+
+    fn fill_image_line(&self, line: usize, payload: &mut [u8]) {
+        let packets_per_row = self.model.width() / PIXELS_PER_PACKET;
+        let y = line / packets_per_row;
+        let x0 = (line % packets_per_row) * PIXELS_PER_PACKET;
+        let bytes_per_pixel = self.cfg.output_format.bytes_per_pixel();
+
+        for (i, pixel) in payload.chunks_exact_mut(bytes_per_pixel).enumerate() {
+            let value = self.pixel_value(x0 + i, y);
+            if bytes_per_pixel == 3 {
+                // A grey LUT is enough to keep patterns distinguishable.
+                pixel.fill((value >> 6) as u8);
+            } else {
+                pixel.copy_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    /// RAW14 value of the output source at image position (`x`, `y`).
+    fn pixel_value(&self, x: usize, y: usize) -> u16 {
+        let value = match self.source {
+            VideoOutputSource::Constant => self.constant as usize,
+            VideoOutputSource::RampH => 0x1000 + x * 16,
+            VideoOutputSource::RampV => 0x1000 + y * 16,
+            VideoOutputSource::Ramp => 0x1000 + (x + y) * 8,
+            // A fixed scene with some texture that changes every frame.
+            VideoOutputSource::Raw | VideoOutputSource::Cooked => {
+                0x1F40 + (x * 7 + y * 13 + self.frame_counter as usize) % 64
+            }
+        };
+        (value & 0x3FFF) as u16
+    }
+
+    fn fill_telemetry_row_a(&self, payload: &mut [u8]) {
+        let mut words = [0u16; TELEMETRY_ROW_WORDS];
+        let time_ms = self.frame_counter.wrapping_mul(FRAME_PERIOD_MS);
+        words[0] = 14;
+        words[1] = time_ms as u16;
+        words[2] = (time_ms >> 16) as u16;
+        words[20] = self.frame_counter as u16;
+        words[21] = (self.frame_counter >> 16) as u16;
+        words[24] = FPA_TEMPERATURE_CK;
+        words[26] = HOUSING_TEMPERATURE_CK;
+        words[72] = self.cfg.output_format as u16;
+
+        for (bytes, word) in payload.chunks_exact_mut(2).zip(words) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+}
+
+/// Recomputes the CRC field after the rest of the packet is final.
+pub(crate) fn set_packet_crc(packet: &mut [u8]) {
+    let crc = lepton_packet_crc16_spec(packet).unwrap_or(0);
+    packet[2..4].copy_from_slice(&crc.to_be_bytes());
+}
+
+pub(crate) fn discard_packet(packet_size: usize) -> Vec<u8> {
+    let mut packet = vec![0u8; packet_size];
+    packet[0] = 0x0F;
+    packet
+}
+
+/// A simulated camera; clone it or use its handles to drive the same device.
+#[derive(Clone)]
+pub struct VirtualLepton {
+    state: Rc<RefCell<State>>,
+}
+
+impl VirtualLepton {
+    pub fn new(model: SensorModel) -> Self {
+        VirtualLepton {
+            state: Rc::new(RefCell::new(State::new(model))),
+        }
+    }
+
+    /// CCI interface, answering at the camera's I2C address `0x2A`.
+    pub fn i2c(&self) -> SimI2c {
+        SimI2c {
+            state: Rc::clone(&self.state),
+        }
+    }
+
+    /// VoSPI interface.
+    pub fn spi(&self) -> SimSpi {
+        SimSpi {
+            state: Rc::clone(&self.state),
+        }
+    }
+
+    /// RESET_L: low holds the camera in reset, the rising edge reboots it.
+    pub fn reset_pin(&self) -> SimPin {
+        SimPin {
+            state: Rc::clone(&self.state),
+            line: SimLine::Reset,
+        }
+    }
+
+    /// PWR_DWN_L: low powers the camera down, high wakes it unless RESET_L is held.
+    pub fn power_down_pin(&self) -> SimPin {
+        SimPin {
+            state: Rc::clone(&self.state),
+            line: SimLine::PowerDown,
+        }
+    }
+
+    pub fn video_output_source(&self) -> VideoOutputSource {
+        self.state.borrow().output_source()
+    }
+
+    /// Raw words last stored by a Set of `command`'s attribute, if any.
+    pub fn attribute(&self, command: LepCommand) -> Option<Vec<u16>> {
+        self.state
+            .borrow()
+            .attributes
+            .get(&attribute(command))
+            .cloned()
+    }
+
+    /// Number of frames started on the VoSPI stream.
+    pub fn frame_counter(&self) -> u32 {
+        self.state.borrow().stream.frame_counter
+    }
+
+    /// Reboots through OEM reboot, RESET_L or power-up.
+    pub fn reboot_count(&self) -> u32 {
+        self.state.borrow().reboots
+    }
+
+    pub fn is_powered_down(&self) -> bool {
+        self.state.borrow().powered_down
+    }
+
+    /// CS, for a driver that holds chip select with a GPIO. Deasserting it restarts a
+    /// stalled VoSPI stream.
+    pub fn cs_pin(&self) -> SimPin {
+        SimPin {
+            state: Rc::clone(&self.state),
+            line: SimLine::Cs,
+        }
+    }
+
+    /// Stalls VoSPI, which then sends only discard packets until CS is deasserted, as a
+    /// camera does after losing sync with the host.
+    pub fn lose_sync(&self) {
+        self.state.borrow_mut().stalled = true;
+    }
+
+    /// Locks the camera up: CCI stops acknowledging and VoSPI sends only discard packets
+    /// until RESET_L or PWR_DWN_L restarts it. An OEM reboot cannot get through.
+    pub fn hang(&self) {
+        self.state.borrow_mut().hung = true;
+    }
+}
+
+/// CCI handle of a [`VirtualLepton`].
+pub struct SimI2c {
+    state: Rc<RefCell<State>>,
+}
+
+impl i2c::ErrorType for SimI2c {
+    type Error = i2c::ErrorKind;
+}
+
+impl I2c for SimI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        if address != CCI_ADDRESS || state.in_reset || state.powered_down || state.hung {
+            return Err(i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        if state.boot_nacks > 0 {
+            state.boot_nacks -= 1;
+            return Err(i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        // The register address auto-increments by one word per transferred word.
+        let mut pointer = 0u16;
+        for operation in operations {
+            match operation {
+                i2c::Operation::Write(bytes) => {
+                    if bytes.len() < 2 {
+                        return Err(i2c::ErrorKind::Other);
+                    }
+                    pointer = u16::from_be_bytes([bytes[0], bytes[1]]);
+                    for chunk in bytes[2..].chunks_exact(2) {
+                        state.write_register(pointer, u16::from_be_bytes([chunk[0], chunk[1]]));
+                        pointer = pointer.wrapping_add(2);
+                    }
+                }
+                i2c::Operation::Read(buffer) => {
+                    for chunk in buffer.chunks_exact_mut(2) {
+                        chunk.copy_from_slice(&state.read_register(pointer).to_be_bytes());
+                        pointer = pointer.wrapping_add(2);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// VoSPI handle of a [`VirtualLepton`]; every read continues the packet stream.
+pub struct SimSpi {
+    state: Rc<RefCell<State>>,
+}
+
+impl spi::ErrorType for SimSpi {
+    type Error = spi::ErrorKind;
+}
+
+impl SpiDevice for SimSpi {
+    fn transaction(
+        &mut self,
+        operations: &mut [spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        for operation in operations {
+            match operation {
+                spi::Operation::Read(buffer) | spi::Operation::TransferInPlace(buffer) => {
+                    state.fill_spi(buffer)
+                }
+                spi::Operation::Transfer(read, _) => state.fill_spi(read),
+                spi::Operation::Write(_) | spi::Operation::DelayNs(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SimLine {
+    Cs,
+    Reset,
+    PowerDown,
+}
+
+/// CS, RESET_L or PWR_DWN_L of a [`VirtualLepton`].
+pub struct SimPin {
+    state: Rc<RefCell<State>>,
+    line: SimLine,
+}
+
+impl digital::ErrorType for SimPin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for SimPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        match self.line {
+            SimLine::Cs => {}
+            SimLine::Reset => state.in_reset = true,
+            SimLine::PowerDown => state.powered_down = true,
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        let was_held = match self.line {
+            SimLine::Cs => {
+                if core::mem::replace(&mut state.stalled, false) {
+                    state.restart_stream();
+                }
+                return Ok(());
+            }
+            SimLine::Reset => core::mem::replace(&mut state.in_reset, false),
+            SimLine::PowerDown => core::mem::replace(&mut state.powered_down, false),
+        };
+        if was_held && !state.in_reset && !state.powered_down {
+            state.reboot();
+        }
+        Ok(())
+    }
+}
+
+/// Delay that returns immediately; the simulation does not model time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimDelay;
+
+impl DelayNs for SimDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agc::AgcRoi;
+    use crate::lepton::{Lepton, LeptonError};
+    use crate::lepton_cci::LEPTONCCI;
+    use crate::recovery::{RecoveryCounters, RecoveryPolicy, RecoveryStep};
+    use crate::vospi::ResyncStrategy;
+
+    fn lepton(sim: &VirtualLepton) -> Lepton<SimI2c, SimSpi, SimDelay> {
+        Lepton::new(sim.i2c(), sim.spi(), SimDelay).unwrap()
+    }
+
+    #[test]
+    fn cci_attributes_read_back_and_reboot_restores_defaults() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut cci = LEPTONCCI::new(sim.i2c(), SimDelay).unwrap();
+        let roi = AgcRoi {
+            start_col: 1,
+            start_row: 2,
+            end_col: 150,
+            end_row: 110,
+        };
+
+        cci.set_agc_roi(roi).unwrap();
+        assert_eq!(cci.get_agc_roi().unwrap().0, roi);
+
+        cci.run_oem_reboot().unwrap();
+        assert_eq!(sim.reboot_count(), 1);
+        assert_eq!(sim.attribute(LepCommand::get_agc_roi()), None);
+    }
+
+    #[test]
+    fn check_camera_passes_and_restores_source() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+
+        let report = lepton.check_camera();
+        for test in &report.tests {
+            assert!(test.ok, "{}: {}", test.name, test.details);
+        }
+        assert!(report.restored);
+        assert_eq!(sim.video_output_source(), VideoOutputSource::Raw);
+    }
+
+    #[test]
+    fn telemetry_reports_stream_frame_counter() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+        lepton.set_telemetry_mode(TelemetryMode::Footer).unwrap();
+
+        let first = lepton.read_frame_robust().unwrap();
+        let second = lepton.read_frame_robust().unwrap();
+        let counter =
+            |frame: &crate::vospi::CapturedFrame| frame.meta.telemetry.unwrap().frame_counter;
+        assert_eq!(counter(&second), counter(&first) + 1);
+        assert_eq!(counter(&second), sim.frame_counter());
+    }
+
+    #[test]
+    fn lepton_captures_from_a_packet_source() {
+        use super::synth::{FaultKind, FaultRates, FaultSchedule, FaultySource};
+
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let cfg = RobustCaptureConfig {
+            telemetry: TelemetryMode::Footer,
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+        let schedule = FaultSchedule {
+            seed: 5,
+            rates: FaultRates {
+                discard_burst: 0.01,
+                ..FaultRates::default()
+            },
+            start_mid_frame: true,
+        };
+        let mut lepton =
+            Lepton::with_packet_source(sim.i2c(), FaultySource::new(&cfg, schedule), SimDelay)
+                .unwrap();
+        lepton.set_robust_config(cfg);
+
+        for _ in 0..3 {
+            let frame = lepton.read_frame_robust().unwrap();
+            let counter = frame.meta.telemetry.unwrap().frame_counter;
+            let source = lepton.vospi_port_mut().source();
+            assert_eq!(frame.pixels, source.expected_image(counter));
+        }
+
+        let source = lepton.vospi_port_mut().source();
+        let discards: u32 = source
+            .injected_read()
+            .map(|fault| match fault.kind {
+                FaultKind::DiscardBurst { len } => len,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(lepton.diagnostics().discard_count, discards);
+    }
+
+    #[test]
+    fn bulk_reads_capture_from_simulated_spi() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+        lepton
+            .set_video_output_source(VideoOutputSource::RampH)
+            .unwrap();
+        let single = lepton.read_frame_robust().unwrap();
+
+        lepton.set_robust_config(RobustCaptureConfig {
+            bulk_read_packets: 60,
+            ..lepton.robust_config()
+        });
+        let bulk = lepton.read_frame_robust().unwrap();
+        assert_eq!(bulk.pixels, single.pixels);
+        assert_eq!(lepton.diagnostics().resync_count, 0);
+    }
+
+    #[test]
+    fn lepton2_rgb888_ramp_is_streamed_unsegmented() {
+        let sim = VirtualLepton::new(SensorModel::Lepton2x);
+        let mut lepton = lepton(&sim);
+        lepton.set_robust_config(RobustCaptureConfig::for_sensor(SensorModel::Lepton2x));
+        lepton
+            .set_video_output_format(VideoOutputFormat::Rgb888)
+            .unwrap();
+        lepton
+            .set_video_output_source(VideoOutputSource::RampV)
+            .unwrap();
+
+        let frame = lepton.read_frame_robust().unwrap();
+        assert_eq!(frame.pixels.len(), 80 * 60 * 3);
+        assert!(frame.rgb_pixel(59 * 80).unwrap()[0] > frame.rgb_pixel(0).unwrap()[0]);
+    }
+
+    #[test]
+    fn pins_power_cycle_and_reset_the_camera() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim)
+            .with_reset_pin(sim.reset_pin())
+            .with_power_down_pin(sim.power_down_pin());
+
+        lepton.hard_reset().unwrap();
+        assert_eq!(sim.reboot_count(), 1);
+
+        lepton.power_down().unwrap();
+        assert!(sim.is_powered_down());
+        assert!(lepton.get_boot_status().is_err());
+
+        lepton.power_up().unwrap();
+        assert!(!sim.is_powered_down());
+        assert_eq!(sim.reboot_count(), 2);
+        assert!(lepton.read_frame_robust().is_ok());
+    }
+
+    #[test]
+    fn idle_resync_restarts_a_stalled_stream() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim).with_cs_pin(sim.cs_pin());
+        sim.lose_sync();
+
+        let err = lepton.read_frame_robust().unwrap_err();
+        assert!(matches!(err, LeptonError::DiscardPacketFlood), "{err:?}");
+        assert_eq!(lepton.recovery_counters(), RecoveryCounters::default());
+
+        lepton.set_recovery_policy(RecoveryPolicy::escalating());
+        assert!(lepton.read_frame_robust().is_ok());
+        assert_eq!(
+            lepton.recovery_counters(),
+            RecoveryCounters {
+                idle_resyncs: 1,
+                recovered: 1,
+                ..RecoveryCounters::default()
+            }
+        );
+        assert_eq!(sim.reboot_count(), 0);
+    }
+
+    #[test]
+    fn cci_reboot_recovers_when_idle_resync_does_not() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+        lepton.set_oem_video_output_enable(false).unwrap();
+        lepton.set_recovery_policy(RecoveryPolicy::escalating());
+
+        assert!(lepton.read_frame_robust().is_ok());
+        assert_eq!(
+            lepton.recovery_counters(),
+            RecoveryCounters {
+                idle_resyncs: 1,
+                cci_reboots: 1,
+                recovered: 1,
+                ..RecoveryCounters::default()
+            }
+        );
+        assert_eq!(sim.reboot_count(), 1);
+    }
+
+    #[test]
+    fn hardware_reset_recovers_a_hung_camera() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim).with_reset_pin(sim.reset_pin());
+        lepton.set_recovery_policy(RecoveryPolicy::escalating());
+        sim.hang();
+
+        assert!(lepton.read_frame_robust().is_ok());
+        assert_eq!(
+            lepton.recovery_counters(),
+            RecoveryCounters {
+                idle_resyncs: 1,
+                cci_reboots: 1,
+                hardware_resets: 1,
+                failed_steps: 1,
+                recovered: 1,
+                unrecovered: 0,
+            }
+        );
+        assert_eq!(sim.reboot_count(), 1);
+    }
+
+    #[test]
+    fn hardware_reset_is_skipped_without_a_reset_pin() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+        for result in [lepton.hard_reset(), lepton.power_down(), lepton.power_up()] {
+            assert!(matches!(result, Err(LeptonError::PinNotConfigured)));
+        }
+        assert!(matches!(
+            lepton.recover(RecoveryStep::HardwareReset),
+            Err(LeptonError::PinNotConfigured)
+        ));
+
+        lepton.set_recovery_policy(RecoveryPolicy::escalating());
+        sim.hang();
+        let err = lepton.read_frame_robust().unwrap_err();
+        assert!(matches!(err, LeptonError::DiscardPacketFlood), "{err:?}");
+        assert_eq!(
+            lepton.recovery_counters(),
+            RecoveryCounters {
+                idle_resyncs: 1,
+                cci_reboots: 1,
+                failed_steps: 1,
+                unrecovered: 1,
+                ..RecoveryCounters::default()
+            }
+        );
+        assert_eq!(sim.reboot_count(), 0);
+    }
+
+    #[test]
+    fn cci_reboot_reports_a_camera_that_does_not_answer() {
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim);
+        lepton.set_recovery_policy(RecoveryPolicy {
+            cci_reboot: true,
+            ..RecoveryPolicy::default()
+        });
+        sim.hang();
+
+        let err = lepton.recover(RecoveryStep::CciReboot).unwrap_err();
+        assert!(matches!(err, LeptonError::I2c(_)), "{err:?}");
+
+        let err = lepton.read_frame_robust().unwrap_err();
+        assert!(matches!(err, LeptonError::DiscardPacketFlood), "{err:?}");
+        assert_eq!(
+            lepton.recovery_counters(),
+            RecoveryCounters {
+                cci_reboots: 2,
+                failed_steps: 2,
+                unrecovered: 1,
+                ..RecoveryCounters::default()
+            }
+        );
+    }
+
+    #[test]
+    fn locked_stream_glitches_are_retried_without_recovery_steps() {
+        use super::synth::{FaultRates, FaultSchedule, FaultySource};
+
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let cfg = RobustCaptureConfig {
+            enable_crc: true,
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+        let schedule = FaultSchedule {
+            seed: 3,
+            rates: FaultRates {
+                crc_corruption: 0.002,
+                ..FaultRates::default()
+            },
+            start_mid_frame: false,
+        };
+        let mut lepton =
+            Lepton::with_packet_source(sim.i2c(), FaultySource::new(&cfg, schedule), SimDelay)
+                .unwrap();
+        lepton.set_robust_config(cfg);
+        lepton.set_recovery_policy(RecoveryPolicy::escalating());
+
+        for _ in 0..10 {
+            lepton.read_frame_robust().unwrap();
+        }
+        assert!(lepton.diagnostics().crc_error_count > 0);
+        assert_eq!(lepton.recovery_counters(), RecoveryCounters::default());
+    }
+
+    #[test]
+    fn cs_idle_reports_cs_pin_failures() {
+        struct BrokenPin;
+
+        impl digital::ErrorType for BrokenPin {
+            type Error = digital::ErrorKind;
+        }
+
+        impl OutputPin for BrokenPin {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                Err(digital::ErrorKind::Other)
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                Err(digital::ErrorKind::Other)
+            }
+        }
+
+        let sim = VirtualLepton::new(SensorModel::Lepton3x);
+        let mut lepton = lepton(&sim).with_cs_pin(BrokenPin);
+        lepton.set_oem_video_output_enable(false).unwrap();
+        lepton.set_robust_config(RobustCaptureConfig {
+            resync_strategy: ResyncStrategy::CsIdle { idle_ms: 185 },
+            ..lepton.robust_config()
+        });
+
+        let err = lepton.read_frame_robust().unwrap_err();
+        assert!(matches!(err, LeptonError::Pin), "{err:?}");
+    }
+}
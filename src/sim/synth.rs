@@ -0,0 +1,552 @@
+//! Seedable VoSPI stream with injected faults, for stress-testing robust capture.
+//!
+//! [`FaultySource`] is a [`PacketSource`] that streams valid Lepton 3.x or 2.x frames (with
+//! telemetry if the capture config enables it) and corrupts them according to a
+//! [`FaultSchedule`]. The same seed always produces the same packets, and every injected
+//! fault is logged with the index of the packet it affected, so tests can assert recovery
+//! and [`FrameDiagnostics`](crate::vospi::FrameDiagnostics) counts exactly.
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+
+use super::{discard_packet, set_packet_crc, FrameStream};
+use crate::vospi::{packets_per_segment, PacketSource, RobustCaptureConfig, SensorModel};
+
+const DEFAULT_MAX_DISCARD_BURST: u32 = 8;
+const PACKET_HEADER_BYTES: usize = 4;
+
+/// Probability of each fault, from `0.0` (never) to `1.0` (always).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultRates {
+    /// Per packet: a burst of discard packets is sent before it.
+    pub discard_burst: f32,
+    /// Longest discard burst; lengths are uniform in `1..=max_discard_burst`.
+    pub max_discard_burst: u32,
+    /// Per packet: one payload byte is flipped, so the CRC no longer matches.
+    pub crc_corruption: f32,
+    /// Per packet: the packet is not sent.
+    pub dropped_line: f32,
+    /// Per packet: the two ID bytes are swapped on the wire.
+    pub byte_swapped_header: f32,
+    /// Per packet: the transfer ends early and the rest of the packet reads as `0xFF`.
+    pub truncated_packet: f32,
+    /// Per segment: the previous segment is sent again before it.
+    pub duplicate_segment: f32,
+    /// Per segment: a whole segment with ID 0 is sent before it (segmented streams only).
+    pub segment_zero: f32,
+}
+
+impl Default for FaultRates {
+    fn default() -> Self {
+        Self {
+            discard_burst: 0.0,
+            max_discard_burst: DEFAULT_MAX_DISCARD_BURST,
+            crc_corruption: 0.0,
+            dropped_line: 0.0,
+            byte_swapped_header: 0.0,
+            truncated_packet: 0.0,
+            duplicate_segment: 0.0,
+            segment_zero: 0.0,
+        }
+    }
+}
+
+/// Seed and fault rates of a [`FaultySource`]; the default injects nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FaultSchedule {
+    pub seed: u64,
+    pub rates: FaultRates,
+    /// Start streaming at a random packet of the first frame instead of its beginning.
+    pub start_mid_frame: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    DiscardBurst {
+        len: u32,
+    },
+    CrcCorruption,
+    DroppedLine,
+    ByteSwappedHeader,
+    /// Only the first `valid_bytes` of the packet were transferred.
+    TruncatedPacket {
+        valid_bytes: usize,
+    },
+    DuplicateSegment,
+    SegmentZero,
+    MidFrameStart {
+        skipped_packets: usize,
+    },
+}
+
+/// One injected fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedFault {
+    /// Index, among all packets read from the source, of the first packet the fault
+    /// affects. A dropped line is logged at the index the packet would have had.
+    pub packet: u64,
+    /// Frame counter (as in telemetry) of the frame being streamed.
+    pub frame: u32,
+    /// Segment of that frame the fault applies to, from 1.
+    pub segment: usize,
+    pub kind: FaultKind,
+}
+
+/// xorshift64, so schedules do not depend on an external RNG crate.
+#[derive(Debug, Clone)]
+struct XorShift64(u64);
+
+impl XorShift64 {
+    fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift.
+        XorShift64(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound.max(1) as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        // 24 random bits are exact in an f32.
+        probability > 0.0 && ((self.next() >> 40) as f32 / (1u32 << 24) as f32) < probability
+    }
+}
+
+/// Packet source streaming a synthetic camera with scheduled faults.
+pub struct FaultySource {
+    stream: FrameStream,
+    schedule: FaultSchedule,
+    rng: XorShift64,
+    pending: VecDeque<Vec<u8>>,
+    produced: u64,
+    read: u64,
+    /// Clean packets of the segment being streamed, for duplicate segments.
+    segment_packets: Vec<Vec<u8>>,
+    previous_segment: Vec<Vec<u8>>,
+    log: Vec<InjectedFault>,
+}
+
+impl FaultySource {
+    /// Streams frames with the geometry, format and telemetry of `cfg`.
+    ///
+    /// A `segments_per_frame` of 1 produces an 80x60 Lepton 2.x stream, anything else a
+    /// 160x120 Lepton 3.x stream. Pixels are a scene that changes with every frame; see
+    /// [`expected_image`](Self::expected_image).
+    pub fn new(cfg: &RobustCaptureConfig, schedule: FaultSchedule) -> Self {
+        let model = if cfg.segments_per_frame == 1 {
+            SensorModel::Lepton2x
+        } else {
+            SensorModel::Lepton3x
+        };
+        let mut source = FaultySource {
+            stream: FrameStream::new(model, *cfg),
+            schedule,
+            rng: XorShift64::new(schedule.seed),
+            pending: VecDeque::new(),
+            produced: 0,
+            read: 0,
+            segment_packets: Vec::new(),
+            previous_segment: Vec::new(),
+            log: Vec::new(),
+        };
+
+        if schedule.start_mid_frame {
+            let skipped_packets = 1 + source.rng.below(source.stream.packets_per_frame() - 1);
+            for _ in 0..skipped_packets {
+                source.stream.next_packet();
+            }
+            let (frame, segment) = source.position();
+            source.log.push(InjectedFault {
+                packet: 0,
+                frame,
+                segment,
+                kind: FaultKind::MidFrameStart { skipped_packets },
+            });
+        }
+
+        source
+    }
+
+    /// Every fault injected so far, in stream order.
+    ///
+    /// Faults are generated slightly ahead of reads; use
+    /// [`injected_read`](Self::injected_read) to see only those in packets already read.
+    pub fn injected(&self) -> &[InjectedFault] {
+        &self.log
+    }
+
+    /// Faults affecting packets that have been read.
+    pub fn injected_read(&self) -> impl Iterator<Item = &InjectedFault> + '_ {
+        self.log
+            .iter()
+            .filter(move |fault| fault.packet < self.read)
+    }
+
+    pub fn packets_read(&self) -> u64 {
+        self.read
+    }
+
+    /// Image a fault-free capture of frame `frame_counter` should produce.
+    pub fn expected_image(&self, frame_counter: u32) -> Vec<u8> {
+        self.stream.image(frame_counter)
+    }
+
+    /// Frame counter and segment of the next packet the stream will generate.
+    fn position(&self) -> (u32, usize) {
+        let frame = if self.stream.at_frame_start() {
+            self.stream.frame_counter.wrapping_add(1)
+        } else {
+            self.stream.frame_counter
+        };
+        let segment = self.stream.packet_index() / packets_per_segment(&self.stream.cfg) + 1;
+        (frame, segment)
+    }
+
+    fn push(&mut self, packet: Vec<u8>) {
+        self.pending.push_back(packet);
+        self.produced += 1;
+    }
+
+    fn record(&mut self, packet: u64, frame: u32, segment: usize, kind: FaultKind) {
+        self.log.push(InjectedFault {
+            packet,
+            frame,
+            segment,
+            kind,
+        });
+    }
+
+    /// Generates the next clean packet and queues it with any scheduled faults.
+    fn produce(&mut self) {
+        let rates = self.schedule.rates;
+        let cfg = self.stream.cfg;
+        let packets_per_segment = packets_per_segment(&cfg);
+        let (frame, segment) = self.position();
+
+        if self
+            .stream
+            .packet_index()
+            .is_multiple_of(packets_per_segment)
+        {
+            self.previous_segment = std::mem::take(&mut self.segment_packets);
+
+            if !self.previous_segment.is_empty() && self.rng.chance(rates.duplicate_segment) {
+                let previous = self.previous_segment.clone();
+                let previous_segment =
+                    (segment + cfg.segments_per_frame - 2) % cfg.segments_per_frame + 1;
+                self.record(
+                    self.produced,
+                    frame,
+                    previous_segment,
+                    FaultKind::DuplicateSegment,
+                );
+                for packet in previous {
+                    self.push(packet);
+                }
+            }
+
+            if cfg.segments_per_frame > 1 && self.rng.chance(rates.segment_zero) {
+                self.record(self.produced, frame, segment, FaultKind::SegmentZero);
+                let mut copy = self.stream.clone();
+                for packet_number in 0..packets_per_segment {
+                    let mut packet = copy.next_packet();
+                    if packet_number == 20 {
+                        packet[0] &= 0x0F;
+                        set_packet_crc(&mut packet);
+                    }
+                    self.push(packet);
+                }
+            }
+        }
+
+        let mut packet = self.stream.next_packet();
+        self.segment_packets.push(packet.clone());
+
+        if self.rng.chance(rates.dropped_line) {
+            self.record(self.produced, frame, segment, FaultKind::DroppedLine);
+            return;
+        }
+
+        if self.rng.chance(rates.discard_burst) {
+            let len = 1 + self.rng.below(rates.max_discard_burst.max(1) as usize) as u32;
+            self.record(
+                self.produced,
+                frame,
+                segment,
+                FaultKind::DiscardBurst { len },
+            );
+            for _ in 0..len {
+                self.push(discard_packet(cfg.packet_size_bytes));
+            }
+        }
+
+        if self.rng.chance(rates.crc_corruption) {
+            let index = PACKET_HEADER_BYTES + self.rng.below(packet.len() - PACKET_HEADER_BYTES);
+            packet[index] ^= 1 << self.rng.below(8);
+            self.record(self.produced, frame, segment, FaultKind::CrcCorruption);
+        }
+
+        if self.rng.chance(rates.byte_swapped_header) {
+            packet.swap(0, 1);
+            self.record(self.produced, frame, segment, FaultKind::ByteSwappedHeader);
+        }
+
+        if self.rng.chance(rates.truncated_packet) {
+            let valid_bytes =
+                PACKET_HEADER_BYTES + self.rng.below(packet.len() - PACKET_HEADER_BYTES);
+            packet[valid_bytes..].fill(0xFF);
+            self.record(
+                self.produced,
+                frame,
+                segment,
+                FaultKind::TruncatedPacket { valid_bytes },
+            );
+        }
+
+        self.push(packet);
+    }
+}
+
+impl PacketSource for FaultySource {
+    type Error = Infallible;
+
+    fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error> {
+        while self.pending.is_empty() {
+            self.produce();
+        }
+
+        let next = self.pending.pop_front().unwrap_or_default();
+        let len = next.len().min(packet.len());
+        packet[..len].copy_from_slice(&next[..len]);
+        packet[len..].fill(0);
+        self.read += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::TelemetryMode;
+    use crate::vospi::{capture_frame_from_source, FrameDiagnostics, SyncState};
+
+    fn telemetry_cfg() -> RobustCaptureConfig {
+        RobustCaptureConfig {
+            telemetry: TelemetryMode::Footer,
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        }
+    }
+
+    fn read_packets(source: &mut FaultySource, count: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; count * 164];
+        for packet in bytes.chunks_exact_mut(164) {
+            source.read_packet(packet).unwrap();
+        }
+        bytes
+    }
+
+    fn count(source: &FaultySource, matches: impl Fn(&FaultKind) -> bool) -> u32 {
+        source
+            .injected_read()
+            .filter(|fault| matches(&fault.kind))
+            .count() as u32
+    }
+
+    #[test]
+    fn same_seed_reproduces_stream_and_log() {
+        let schedule = FaultSchedule {
+            seed: 7,
+            rates: FaultRates {
+                discard_burst: 0.01,
+                crc_corruption: 0.01,
+                dropped_line: 0.01,
+                byte_swapped_header: 0.01,
+                truncated_packet: 0.01,
+                duplicate_segment: 0.1,
+                segment_zero: 0.1,
+                ..FaultRates::default()
+            },
+            start_mid_frame: true,
+        };
+        let cfg = RobustCaptureConfig::default();
+
+        let mut first = FaultySource::new(&cfg, schedule);
+        let mut second = FaultySource::new(&cfg, schedule);
+        assert_eq!(
+            read_packets(&mut first, 2000),
+            read_packets(&mut second, 2000)
+        );
+        assert_eq!(first.injected(), second.injected());
+        assert!(matches!(
+            first.injected()[0].kind,
+            FaultKind::MidFrameStart { .. }
+        ));
+
+        let mut other = FaultySource::new(
+            &cfg,
+            FaultSchedule {
+                seed: 8,
+                ..schedule
+            },
+        );
+        read_packets(&mut other, 2000);
+        assert_ne!(first.injected(), other.injected());
+    }
+
+    #[test]
+    fn discards_and_invalid_segments_are_counted_exactly() {
+        let cfg = telemetry_cfg();
+        let mut source = FaultySource::new(
+            &cfg,
+            FaultSchedule {
+                seed: 42,
+                rates: FaultRates {
+                    discard_burst: 0.02,
+                    segment_zero: 0.2,
+                    ..FaultRates::default()
+                },
+                ..FaultSchedule::default()
+            },
+        );
+        let mut synced = false;
+        let mut state = SyncState::Unsynced;
+        let mut diag = FrameDiagnostics::default();
+
+        for _ in 0..10 {
+            let frame = capture_frame_from_source(
+                &mut source,
+                &cfg,
+                &mut synced,
+                &mut state,
+                &mut diag,
+                || 0,
+            )
+            .unwrap();
+            let counter = frame.meta.telemetry.unwrap().frame_counter;
+            assert_eq!(frame.pixels, source.expected_image(counter));
+        }
+
+        let discards: u32 = source
+            .injected_read()
+            .map(|fault| match fault.kind {
+                FaultKind::DiscardBurst { len } => len,
+                _ => 0,
+            })
+            .sum();
+        assert!(discards > 0);
+        assert_eq!(diag.discard_count, discards);
+        let invalid = count(&source, |kind| *kind == FaultKind::SegmentZero);
+        assert!(invalid > 0);
+        assert_eq!(diag.invalid_segment_count, invalid);
+    }
+
+    #[test]
+    fn bulk_reads_capture_like_single_reads() {
+        let cfg = telemetry_cfg();
+        let schedule = FaultSchedule {
+            seed: 9,
+            rates: FaultRates {
+                discard_burst: 0.01,
+                crc_corruption: 0.002,
+                segment_zero: 0.1,
+                ..FaultRates::default()
+            },
+            start_mid_frame: true,
+        };
+        let capture = |cfg: RobustCaptureConfig| {
+            let cfg = RobustCaptureConfig {
+                enable_crc: true,
+                ..cfg
+            };
+            let mut source = FaultySource::new(&cfg, schedule);
+            let mut state = SyncState::Unsynced;
+            let mut diag = FrameDiagnostics::default();
+            // Unsynced calls abandon segments on CRC errors instead of returning, so no
+            // read-ahead packets are dropped.
+            let frames: Vec<_> = (0..5)
+                .map(|_| {
+                    let mut synced = false;
+                    capture_frame_from_source(
+                        &mut source,
+                        &cfg,
+                        &mut synced,
+                        &mut state,
+                        &mut diag,
+                        || 0,
+                    )
+                    .unwrap()
+                    .pixels
+                })
+                .collect();
+            (
+                frames,
+                diag.discard_count,
+                diag.crc_error_count,
+                diag.invalid_segment_count,
+                source.packets_read(),
+            )
+        };
+
+        let single = capture(cfg);
+        let bulk = capture(RobustCaptureConfig {
+            bulk_read_packets: 61,
+            ..cfg
+        });
+        assert_eq!(bulk, single);
+    }
+
+    #[test]
+    fn every_corrupted_packet_is_reported_as_crc_error() {
+        let cfg = RobustCaptureConfig {
+            enable_crc: true,
+            ..telemetry_cfg()
+        };
+        let mut source = FaultySource::new(
+            &cfg,
+            FaultSchedule {
+                seed: 3,
+                rates: FaultRates {
+                    crc_corruption: 0.002,
+                    ..FaultRates::default()
+                },
+                start_mid_frame: true,
+            },
+        );
+        let mut state = SyncState::Unsynced;
+        let mut diag = FrameDiagnostics::default();
+
+        for _ in 0..10 {
+            // Seeking from segment 1 each time keeps CRC errors inside the capture call.
+            let mut synced = false;
+            let frame = capture_frame_from_source(
+                &mut source,
+                &cfg,
+                &mut synced,
+                &mut state,
+                &mut diag,
+                || 0,
+            )
+            .unwrap();
+            let counter = frame.meta.telemetry.unwrap().frame_counter;
+            assert_eq!(frame.pixels, source.expected_image(counter));
+        }
+
+        let corrupted = count(&source, |kind| *kind == FaultKind::CrcCorruption);
+        assert!(corrupted > 0);
+        assert_eq!(diag.crc_error_count, corrupted);
+    }
+}
@@ -0,0 +1,740 @@
+//! Typed values for the FLIR Lepton SYS module (`0x0200`).
+//!
+//! Layouts follow the Lepton Software IDD Rev 303 SYS command descriptions. Multi-word
+//! values are transferred least-significant word first (see [`CciWords`]).
+
+use core::time::Duration;
+
+use crate::lepton_cci::{words_to_u32, CciWords, DecodeError};
+
+/// Camera operating state reported by SYS Camera Status (`0x0204`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysCameraState {
+    Ready = 0,
+    Initializing = 1,
+    LowPowerMode = 2,
+    GoingIntoStandby = 3,
+    FlatFieldInProcess = 4,
+    FlatFieldImminent = 5,
+    ThermalShutdownImminent = 6,
+}
+
+impl TryFrom<u32> for SysCameraState {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SysCameraState::Ready),
+            1 => Ok(SysCameraState::Initializing),
+            2 => Ok(SysCameraState::LowPowerMode),
+            3 => Ok(SysCameraState::GoingIntoStandby),
+            4 => Ok(SysCameraState::FlatFieldInProcess),
+            5 => Ok(SysCameraState::FlatFieldImminent),
+            6 => Ok(SysCameraState::ThermalShutdownImminent),
+            _ => Err(DecodeError::new("SysCameraState", value)),
+        }
+    }
+}
+
+/// SYS Camera Status (`0x0204`), four data words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SysCameraStatus {
+    pub state: SysCameraState,
+    pub command_count: u16,
+    pub reserved: u16,
+}
+
+impl CciWords for SysCameraStatus {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(SysCameraStatus {
+            state: SysCameraState::try_from(words_to_u32(&words[0..2]))?,
+            command_count: words[2],
+            reserved: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        (self.state as u32).to_words(&mut words[0..2]);
+        words[2] = self.command_count;
+        words[3] = self.reserved;
+    }
+}
+
+/// 64-bit FLIR serial number (`0x0208`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SerialNumber(pub u64);
+
+impl CciWords for SerialNumber {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        let value = words[..4]
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &word| (acc << 16) | word as u64);
+        Ok(SerialNumber(value))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        for (idx, word) in words[..4].iter_mut().enumerate() {
+            *word = (self.0 >> (16 * idx)) as u16;
+        }
+    }
+}
+
+impl core::fmt::Display for SerialNumber {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:016X}", self.0)
+    }
+}
+
+/// 32-byte customer serial number (`0x0228`), programmed at integration time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomerSerialNumber(pub [u8; 32]);
+
+impl CciWords for CustomerSerialNumber {
+    const WORDS: usize = 16;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(2).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(CustomerSerialNumber(bytes))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        for (word, chunk) in words.iter_mut().zip(self.0.chunks_exact(2)) {
+            *word = u16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+    }
+}
+
+/// Temperature in hundredths of a Kelvin, the unit used by every Lepton temperature field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CentiKelvin(pub u16);
+
+impl CentiKelvin {
+    pub fn kelvin(self) -> f32 {
+        self.0 as f32 / 100.0
+    }
+
+    pub fn celsius(self) -> f32 {
+        self.kelvin() - 273.15
+    }
+}
+
+impl CciWords for CentiKelvin {
+    const WORDS: usize = 1;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(CentiKelvin(words[0]))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.0;
+    }
+}
+
+/// Camera uptime (`0x020C`), reported by the camera in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Uptime(pub u32);
+
+impl Uptime {
+    pub fn as_duration(self) -> Duration {
+        Duration::from_millis(self.0 as u64)
+    }
+}
+
+impl CciWords for Uptime {
+    const WORDS: usize = 2;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(Uptime(words_to_u32(words)))
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        self.0.to_words(words);
+    }
+}
+
+/// Position of the telemetry lines within the VoSPI frame (`0x021C`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryLocation {
+    Header = 0,
+    Footer = 1,
+}
+
+impl TryFrom<u32> for TelemetryLocation {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TelemetryLocation::Header),
+            1 => Ok(TelemetryLocation::Footer),
+            _ => Err(DecodeError::new("TelemetryLocation", value)),
+        }
+    }
+}
+
+impl From<TelemetryLocation> for u32 {
+    fn from(value: TelemetryLocation) -> Self {
+        value as u32
+    }
+}
+
+/// Whether telemetry lines are sent with each frame, and where.
+///
+/// Combines the SYS telemetry enable (`0x0218`) and [`TelemetryLocation`] (`0x021C`)
+/// commands. Telemetry adds lines to each frame that must be accounted for when
+/// capturing over VoSPI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryMode {
+    Disabled,
+    Header,
+    Footer,
+}
+
+impl TelemetryMode {
+    /// Location of the telemetry lines, or `None` when telemetry is disabled.
+    pub fn location(self) -> Option<TelemetryLocation> {
+        match self {
+            TelemetryMode::Disabled => None,
+            TelemetryMode::Header => Some(TelemetryLocation::Header),
+            TelemetryMode::Footer => Some(TelemetryLocation::Footer),
+        }
+    }
+}
+
+impl From<Option<TelemetryLocation>> for TelemetryMode {
+    fn from(value: Option<TelemetryLocation>) -> Self {
+        match value {
+            None => TelemetryMode::Disabled,
+            Some(TelemetryLocation::Header) => TelemetryMode::Header,
+            Some(TelemetryLocation::Footer) => TelemetryMode::Footer,
+        }
+    }
+}
+
+/// Number of frames averaged by the SYS frame-average run command (`0x0224`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FramesToAverage {
+    Div1 = 0,
+    Div2 = 1,
+    Div4 = 2,
+    Div8 = 3,
+    Div16 = 4,
+    Div32 = 5,
+    Div64 = 6,
+    Div128 = 7,
+}
+
+impl TryFrom<u32> for FramesToAverage {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FramesToAverage::Div1),
+            1 => Ok(FramesToAverage::Div2),
+            2 => Ok(FramesToAverage::Div4),
+            3 => Ok(FramesToAverage::Div8),
+            4 => Ok(FramesToAverage::Div16),
+            5 => Ok(FramesToAverage::Div32),
+            6 => Ok(FramesToAverage::Div64),
+            7 => Ok(FramesToAverage::Div128),
+            _ => Err(DecodeError::new("FramesToAverage", value)),
+        }
+    }
+}
+
+impl From<FramesToAverage> for u32 {
+    fn from(value: FramesToAverage) -> Self {
+        value as u32
+    }
+}
+
+/// SYS Scene Statistics (`0x022C`), computed over the scene ROI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneStatistics {
+    pub mean_intensity: u16,
+    pub max_intensity: u16,
+    pub min_intensity: u16,
+    pub num_pixels: u16,
+}
+
+impl CciWords for SceneStatistics {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(SceneStatistics {
+            mean_intensity: words[0],
+            max_intensity: words[1],
+            min_intensity: words[2],
+            num_pixels: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.mean_intensity;
+        words[1] = self.max_intensity;
+        words[2] = self.min_intensity;
+        words[3] = self.num_pixels;
+    }
+}
+
+/// SYS Scene ROI (`0x0230`), inclusive pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SceneRoi {
+    pub start_col: u16,
+    pub start_row: u16,
+    pub end_col: u16,
+    pub end_row: u16,
+}
+
+impl CciWords for SceneRoi {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(SceneRoi {
+            start_col: words[0],
+            start_row: words[1],
+            end_col: words[2],
+            end_row: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.start_col;
+        words[1] = self.start_row;
+        words[2] = self.end_col;
+        words[3] = self.end_row;
+    }
+}
+
+/// Shutter position (`0x0238`). The camera reports `Unknown` as `-1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutterPosition {
+    Unknown,
+    Idle,
+    Open,
+    Closed,
+    /// Shutter motor brake engaged.
+    BrakeOn,
+}
+
+impl TryFrom<u32> for ShutterPosition {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, DecodeError> {
+        match value as i32 {
+            -1 => Ok(ShutterPosition::Unknown),
+            0 => Ok(ShutterPosition::Idle),
+            1 => Ok(ShutterPosition::Open),
+            2 => Ok(ShutterPosition::Closed),
+            3 => Ok(ShutterPosition::BrakeOn),
+            _ => Err(DecodeError::new("ShutterPosition", value)),
+        }
+    }
+}
+
+impl From<ShutterPosition> for u32 {
+    fn from(value: ShutterPosition) -> Self {
+        let raw: i32 = match value {
+            ShutterPosition::Unknown => -1,
+            ShutterPosition::Idle => 0,
+            ShutterPosition::Open => 1,
+            ShutterPosition::Closed => 2,
+            ShutterPosition::BrakeOn => 3,
+        };
+        raw as u32
+    }
+}
+
+/// FFC shutter control mode, part of [`FfcShutterMode`].
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutterMode {
+    Manual = 0,
+    Auto = 1,
+    External = 2,
+}
+
+impl TryFrom<u32> for ShutterMode {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ShutterMode::Manual),
+            1 => Ok(ShutterMode::Auto),
+            2 => Ok(ShutterMode::External),
+            _ => Err(DecodeError::new("ShutterMode", value)),
+        }
+    }
+}
+
+/// Shutter temperature lockout state, part of [`FfcShutterMode`].
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutterTempLockout {
+    Inactive = 0,
+    High = 1,
+    Low = 2,
+}
+
+impl TryFrom<u32> for ShutterTempLockout {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ShutterTempLockout::Inactive),
+            1 => Ok(ShutterTempLockout::High),
+            2 => Ok(ShutterTempLockout::Low),
+            _ => Err(DecodeError::new("ShutterTempLockout", value)),
+        }
+    }
+}
+
+/// SYS FFC Shutter Mode object (`0x023C`), sixteen data words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FfcShutterMode {
+    pub shutter_mode: ShutterMode,
+    pub temp_lockout_state: ShutterTempLockout,
+    pub video_freeze_during_ffc: bool,
+    pub ffc_desired: bool,
+    /// Milliseconds since the last FFC.
+    pub elapsed_time_since_last_ffc: u32,
+    /// Milliseconds between automatic FFCs.
+    pub desired_ffc_period: u32,
+    pub explicit_cmd_to_open: bool,
+    pub desired_ffc_temp_delta: CentiKelvin,
+    /// Frames between the FFC-imminent notification and the FFC itself.
+    pub imminent_delay: u16,
+}
+
+impl CciWords for FfcShutterMode {
+    const WORDS: usize = 16;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(FfcShutterMode {
+            shutter_mode: ShutterMode::try_from(words_to_u32(&words[0..2]))?,
+            temp_lockout_state: ShutterTempLockout::try_from(words_to_u32(&words[2..4]))?,
+            video_freeze_during_ffc: words_to_u32(&words[4..6]) != 0,
+            ffc_desired: words_to_u32(&words[6..8]) != 0,
+            elapsed_time_since_last_ffc: words_to_u32(&words[8..10]),
+            desired_ffc_period: words_to_u32(&words[10..12]),
+            explicit_cmd_to_open: words_to_u32(&words[12..14]) != 0,
+            desired_ffc_temp_delta: CentiKelvin(words[14]),
+            imminent_delay: words[15],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        (self.shutter_mode as u32).to_words(&mut words[0..2]);
+        (self.temp_lockout_state as u32).to_words(&mut words[2..4]);
+        (self.video_freeze_during_ffc as u32).to_words(&mut words[4..6]);
+        (self.ffc_desired as u32).to_words(&mut words[6..8]);
+        self.elapsed_time_since_last_ffc.to_words(&mut words[8..10]);
+        self.desired_ffc_period.to_words(&mut words[10..12]);
+        (self.explicit_cmd_to_open as u32).to_words(&mut words[12..14]);
+        words[14] = self.desired_ffc_temp_delta.0;
+        words[15] = self.imminent_delay;
+    }
+}
+
+/// Status of the most recent SYS run command (`0x0244`), e.g. FFC or frame averaging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysRunStatus {
+    WriteError,
+    Error,
+    Ready,
+    Busy,
+    FrameAverageCollectingFrames,
+}
+
+impl TryFrom<u32> for SysRunStatus {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, DecodeError> {
+        match value as i32 {
+            -2 => Ok(SysRunStatus::WriteError),
+            -1 => Ok(SysRunStatus::Error),
+            0 => Ok(SysRunStatus::Ready),
+            1 => Ok(SysRunStatus::Busy),
+            2 => Ok(SysRunStatus::FrameAverageCollectingFrames),
+            _ => Err(DecodeError::new("SysRunStatus", value)),
+        }
+    }
+}
+
+impl From<SysRunStatus> for u32 {
+    fn from(value: SysRunStatus) -> Self {
+        let raw: i32 = match value {
+            SysRunStatus::WriteError => -2,
+            SysRunStatus::Error => -1,
+            SysRunStatus::Ready => 0,
+            SysRunStatus::Busy => 1,
+            SysRunStatus::FrameAverageCollectingFrames => 2,
+        };
+        raw as u32
+    }
+}
+
+/// Sensor gain mode (`0x0248`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GainMode {
+    High = 0,
+    Low = 1,
+    Auto = 2,
+}
+
+impl TryFrom<u32> for GainMode {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(GainMode::High),
+            1 => Ok(GainMode::Low),
+            2 => Ok(GainMode::Auto),
+            _ => Err(DecodeError::new("GainMode", value)),
+        }
+    }
+}
+
+impl From<GainMode> for u32 {
+    fn from(value: GainMode) -> Self {
+        value as u32
+    }
+}
+
+/// Progress of the most recent flat-field correction (`0x024C`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfcState {
+    NeverCommanded = 0,
+    Imminent = 1,
+    InProcess = 2,
+    Done = 3,
+}
+
+impl TryFrom<u32> for FfcState {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FfcState::NeverCommanded),
+            1 => Ok(FfcState::Imminent),
+            2 => Ok(FfcState::InProcess),
+            3 => Ok(FfcState::Done),
+            _ => Err(DecodeError::new("FfcState", value)),
+        }
+    }
+}
+
+impl From<FfcState> for u32 {
+    fn from(value: FfcState) -> Self {
+        value as u32
+    }
+}
+
+/// Thresholds that switch [`GainMode::Auto`] between high and low gain.
+///
+/// A switch happens when more than `p_*` percent of the ROI pixels cross the matching
+/// temperature threshold, given in degrees Celsius (`c_*`, radiometric cameras) or Kelvin
+/// (`t_*`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GainModeThresholds {
+    pub p_high_to_low: u16,
+    pub p_low_to_high: u16,
+    pub c_high_to_low: u16,
+    pub c_low_to_high: u16,
+    pub t_high_to_low: u16,
+    pub t_low_to_high: u16,
+}
+
+/// SYS Gain Mode Object (`0x0250`), fourteen data words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GainModeObject {
+    /// Pixels evaluated for automatic gain switching.
+    pub roi: SceneRoi,
+    pub thresholds: GainModeThresholds,
+    /// Pixels in `roi`; computed by the camera and ignored on Set.
+    pub roi_population: u16,
+    /// Whether the temperature thresholds are usable (TLinear available).
+    pub temp_enabled: bool,
+    /// Flux equivalents of the temperature thresholds; computed by the camera.
+    pub flux_threshold_low_to_high: u16,
+    pub flux_threshold_high_to_low: u16,
+}
+
+impl CciWords for GainModeObject {
+    const WORDS: usize = 14;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(GainModeObject {
+            roi: SceneRoi::from_words(&words[0..4])?,
+            thresholds: GainModeThresholds {
+                p_high_to_low: words[4],
+                p_low_to_high: words[5],
+                c_high_to_low: words[6],
+                c_low_to_high: words[7],
+                t_high_to_low: words[8],
+                t_low_to_high: words[9],
+            },
+            roi_population: words[10],
+            temp_enabled: words[11] != 0,
+            flux_threshold_low_to_high: words[12],
+            flux_threshold_high_to_low: words[13],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        self.roi.to_words(&mut words[0..4]);
+        words[4] = self.thresholds.p_high_to_low;
+        words[5] = self.thresholds.p_low_to_high;
+        words[6] = self.thresholds.c_high_to_low;
+        words[7] = self.thresholds.c_low_to_high;
+        words[8] = self.thresholds.t_high_to_low;
+        words[9] = self.thresholds.t_low_to_high;
+        words[10] = self.roi_population;
+        words[11] = self.temp_enabled as u16;
+        words[12] = self.flux_threshold_low_to_high;
+        words[13] = self.flux_threshold_high_to_low;
+    }
+}
+
+crate::lepton_cci::cci_words_via_u32!(
+    TelemetryLocation,
+    FramesToAverage,
+    ShutterPosition,
+    SysRunStatus,
+    GainMode,
+    FfcState
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lepton_command::LepCommand;
+
+    #[test]
+    fn sys_commands_match_idd_ids() {
+        assert_eq!(LepCommand::get_sys_cam_status().raw_command_id(), 0x0204);
+        assert_eq!(
+            LepCommand::get_sys_flir_serial_number().raw_command_id(),
+            0x0208
+        );
+        assert_eq!(LepCommand::get_sys_cam_uptime().raw_command_id(), 0x020C);
+        assert_eq!(
+            LepCommand::get_sys_fpa_temperature_kelvin().raw_command_id(),
+            0x0214
+        );
+        assert_eq!(
+            LepCommand::get_sys_ffc_shutter_mode().raw_command_id(),
+            0x023C
+        );
+        assert_eq!(LepCommand::get_sys_ffc_status().raw_command_id(), 0x0244);
+        assert_eq!(LepCommand::get_sys_ffc_state().raw_command_id(), 0x024C);
+        assert_eq!(
+            LepCommand::set_sys_gain_mode_object().raw_command_id(),
+            0x0251
+        );
+        assert_eq!(LepCommand::run_sys_ping().raw_command_id(), 0x0202);
+        assert_eq!(LepCommand::run_sys_ffc().raw_command_id(), 0x0242);
+        assert_eq!(LepCommand::run_sys_frame_average().raw_command_id(), 0x0222);
+    }
+
+    #[test]
+    fn serial_number_is_least_significant_word_first() {
+        let serial = SerialNumber::from_words(&[0x4444, 0x3333, 0x2222, 0x1111]).unwrap();
+        assert_eq!(serial, SerialNumber(0x1111_2222_3333_4444));
+
+        let mut words = [0u16; 4];
+        serial.to_words(&mut words);
+        assert_eq!(words, [0x4444, 0x3333, 0x2222, 0x1111]);
+    }
+
+    #[test]
+    fn camera_status_rejects_unknown_state() {
+        let err = SysCameraStatus::from_words(&[9, 0, 1, 0]).unwrap_err();
+        assert_eq!(err, DecodeError::new("SysCameraState", 9));
+    }
+
+    #[test]
+    fn shutter_position_round_trips_negative_unknown() {
+        let mut words = [0u16; 2];
+        ShutterPosition::Unknown.to_words(&mut words);
+        assert_eq!(words, [0xFFFF, 0xFFFF]);
+        assert_eq!(
+            ShutterPosition::from_words(&words).unwrap(),
+            ShutterPosition::Unknown
+        );
+    }
+
+    #[test]
+    fn shutter_position_three_is_brake_on() {
+        assert_eq!(
+            ShutterPosition::try_from(3).unwrap(),
+            ShutterPosition::BrakeOn
+        );
+        assert_eq!(u32::from(ShutterPosition::BrakeOn), 3);
+    }
+
+    #[test]
+    fn gain_mode_object_round_trips() {
+        let object = GainModeObject {
+            roi: SceneRoi {
+                start_col: 0,
+                start_row: 0,
+                end_col: 159,
+                end_row: 119,
+            },
+            thresholds: GainModeThresholds {
+                p_high_to_low: 95,
+                p_low_to_high: 90,
+                c_high_to_low: 110,
+                c_low_to_high: 90,
+                t_high_to_low: 383,
+                t_low_to_high: 363,
+            },
+            roi_population: 19_200,
+            temp_enabled: true,
+            flux_threshold_low_to_high: 0x1234,
+            flux_threshold_high_to_low: 0x2345,
+        };
+        let mut words = [0u16; GainModeObject::WORDS];
+        object.to_words(&mut words);
+        assert_eq!(&words[..6], &[0, 0, 159, 119, 95, 90]);
+        assert_eq!(GainModeObject::from_words(&words).unwrap(), object);
+    }
+
+    #[test]
+    fn ffc_state_decodes() {
+        assert_eq!(FfcState::from_words(&[3, 0]).unwrap(), FfcState::Done);
+        assert!(FfcState::from_words(&[4, 0]).is_err());
+    }
+
+    #[test]
+    fn ffc_shutter_mode_round_trips() {
+        let mode = FfcShutterMode {
+            shutter_mode: ShutterMode::Auto,
+            temp_lockout_state: ShutterTempLockout::Inactive,
+            video_freeze_during_ffc: true,
+            ffc_desired: false,
+            elapsed_time_since_last_ffc: 0x0001_2345,
+            desired_ffc_period: 180_000,
+            explicit_cmd_to_open: false,
+            desired_ffc_temp_delta: CentiKelvin(300),
+            imminent_delay: 52,
+        };
+        let mut words = [0u16; FfcShutterMode::WORDS];
+        mode.to_words(&mut words);
+        assert_eq!(&words[8..10], &[0x2345, 0x0001]);
+        assert_eq!(FfcShutterMode::from_words(&words).unwrap(), mode);
+    }
+}
@@ -0,0 +1,148 @@
+//! Decoding of the VoSPI telemetry lines sent with each frame when SYS telemetry is enabled.
+//!
+//! Telemetry row A carries the per-frame camera state; rows B and C are reserved or
+//! radiometry-specific and are not decoded here. Words are big-endian on the wire and
+//! 32-bit values are least-significant word first, as in the CCI data registers.
+//! See the Lepton 3.5 datasheet, "Telemetry Modes".
+
+use crate::agc::AgcRoi;
+use crate::oem::VideoOutputFormat;
+use crate::sys::{CentiKelvin, FfcState, GainMode};
+
+/// Number of 16-bit words in one telemetry row.
+pub const TELEMETRY_ROW_WORDS: usize = 80;
+/// Payload bytes of one telemetry row.
+pub const TELEMETRY_ROW_BYTES: usize = TELEMETRY_ROW_WORDS * 2;
+/// Telemetry rows A, B and C.
+pub const TELEMETRY_ROWS: usize = 3;
+
+const STATUS_FFC_DESIRED_BIT: u32 = 1 << 3;
+const STATUS_FFC_STATE_SHIFT: u32 = 4;
+const STATUS_AGC_ENABLED_BIT: u32 = 1 << 12;
+const STATUS_SHUTTER_LOCKOUT_BIT: u32 = 1 << 15;
+const STATUS_OVERTEMP_SHUTDOWN_BIT: u32 = 1 << 20;
+
+/// Decoded telemetry row A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Telemetry {
+    pub revision: u16,
+    /// Milliseconds since the camera booted.
+    pub time_counter_ms: u32,
+    /// Raw status bits; see the accessor methods for the decoded flags.
+    pub status_bits: u32,
+    pub frame_counter: u32,
+    pub frame_mean: u16,
+    pub fpa_temperature: CentiKelvin,
+    pub housing_temperature: CentiKelvin,
+    pub fpa_temperature_at_last_ffc: CentiKelvin,
+    pub time_counter_at_last_ffc_ms: u32,
+    pub housing_temperature_at_last_ffc: CentiKelvin,
+    pub agc_roi: AgcRoi,
+    pub agc_clip_limit_high: u16,
+    pub agc_clip_limit_low: u16,
+    /// Only reported by Lepton 3.5 and later firmware.
+    pub gain_mode: Option<GainMode>,
+    /// Gain actually in use; differs from `gain_mode` when that is [`GainMode::Auto`].
+    pub effective_gain_mode: Option<GainMode>,
+    pub video_output_format: Option<VideoOutputFormat>,
+    /// Log2 of the number of frames integrated during FFC.
+    pub log2_ffc_frames: u16,
+}
+
+impl Telemetry {
+    /// Decodes telemetry row A from the payload of its VoSPI packet.
+    ///
+    /// Returns `None` if `payload` is shorter than one telemetry row.
+    pub fn from_row_a(payload: &[u8]) -> Option<Self> {
+        if payload.len() < TELEMETRY_ROW_BYTES {
+            return None;
+        }
+
+        let word = |index: usize| u16::from_be_bytes([payload[index * 2], payload[index * 2 + 1]]);
+        let dword = |index: usize| (word(index) as u32) | ((word(index + 1) as u32) << 16);
+        let gain_mode = |index: usize| GainMode::try_from(word(index) as u32).ok();
+
+        Some(Telemetry {
+            revision: word(0),
+            time_counter_ms: dword(1),
+            status_bits: dword(3),
+            frame_counter: dword(20),
+            frame_mean: word(22),
+            fpa_temperature: CentiKelvin(word(24)),
+            housing_temperature: CentiKelvin(word(26)),
+            fpa_temperature_at_last_ffc: CentiKelvin(word(29)),
+            time_counter_at_last_ffc_ms: dword(30),
+            housing_temperature_at_last_ffc: CentiKelvin(word(32)),
+            // Reported top, left, bottom, right.
+            agc_roi: AgcRoi {
+                start_row: word(34),
+                start_col: word(35),
+                end_row: word(36),
+                end_col: word(37),
+            },
+            agc_clip_limit_high: word(38),
+            agc_clip_limit_low: word(39),
+            gain_mode: gain_mode(48),
+            effective_gain_mode: gain_mode(49),
+            video_output_format: u16::try_from(dword(72))
+                .ok()
+                .and_then(|format| VideoOutputFormat::try_from(format).ok()),
+            log2_ffc_frames: word(74),
+        })
+    }
+
+    pub fn ffc_desired(&self) -> bool {
+        self.status_bits & STATUS_FFC_DESIRED_BIT != 0
+    }
+
+    /// FFC progress from the status bits, with the same values as SYS FFC State.
+    pub fn ffc_state(&self) -> FfcState {
+        match (self.status_bits >> STATUS_FFC_STATE_SHIFT) & 0x3 {
+            0 => FfcState::NeverCommanded,
+            1 => FfcState::Imminent,
+            2 => FfcState::InProcess,
+            _ => FfcState::Done,
+        }
+    }
+
+    pub fn agc_enabled(&self) -> bool {
+        self.status_bits & STATUS_AGC_ENABLED_BIT != 0
+    }
+
+    pub fn shutter_lockout(&self) -> bool {
+        self.status_bits & STATUS_SHUTTER_LOCKOUT_BIT != 0
+    }
+
+    pub fn overtemp_shutdown_imminent(&self) -> bool {
+        self.status_bits & STATUS_OVERTEMP_SHUTDOWN_BIT != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_a_decodes_counters_and_status() {
+        let mut words = [0u16; TELEMETRY_ROW_WORDS];
+        words[3] = (2 << STATUS_FFC_STATE_SHIFT) as u16 | STATUS_AGC_ENABLED_BIT as u16;
+        words[20] = 0x0002;
+        words[21] = 0x0001;
+        words[24] = 30_215;
+        words[26] = 30_415;
+        words[72] = VideoOutputFormat::Raw14 as u16;
+        let payload: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+
+        let telemetry = Telemetry::from_row_a(&payload).unwrap();
+        assert_eq!(telemetry.frame_counter, 0x0001_0002);
+        assert_eq!(telemetry.fpa_temperature, CentiKelvin(30_215));
+        assert_eq!(telemetry.housing_temperature, CentiKelvin(30_415));
+        assert_eq!(telemetry.ffc_state(), FfcState::InProcess);
+        assert!(telemetry.agc_enabled());
+        assert_eq!(
+            telemetry.video_output_format,
+            Some(VideoOutputFormat::Raw14)
+        );
+        assert!(Telemetry::from_row_a(&payload[..100]).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! Typed values for the FLIR Lepton VID (video processing) module (`0x0300`).
+//!
+//! Polarity and pseudo-color LUT selection only affect AGC-processed output; the LUT is
+//! applied when the OEM video output format is RGB888. See Lepton Software IDD Rev 303.
+
+use crate::lepton_cci::{CciWords, DecodeError};
+
+/// Output polarity (`0x0300`).
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Polarity {
+    WhiteHot = 0,
+    BlackHot = 1,
+}
+
+impl TryFrom<u32> for Polarity {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Polarity::WhiteHot),
+            1 => Ok(Polarity::BlackHot),
+            _ => Err(DecodeError::new("Polarity", value)),
+        }
+    }
+}
+
+impl From<Polarity> for u32 {
+    fn from(value: Polarity) -> Self {
+        value as u32
+    }
+}
+
+/// Pseudo-color lookup table applied in RGB888 mode (`0x0304`).
+///
+/// `Fusion` is the iron-style palette usually called "ironbow" elsewhere.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PseudoColorLut {
+    Wheel6 = 0,
+    Fusion = 1,
+    Rainbow = 2,
+    Glowbow = 3,
+    Sepia = 4,
+    Color = 5,
+    IceFire = 6,
+    Rain = 7,
+    /// LUT uploaded by the host with the VID user LUT transfer command.
+    User = 8,
+}
+
+impl TryFrom<u32> for PseudoColorLut {
+    type Error = DecodeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PseudoColorLut::Wheel6),
+            1 => Ok(PseudoColorLut::Fusion),
+            2 => Ok(PseudoColorLut::Rainbow),
+            3 => Ok(PseudoColorLut::Glowbow),
+            4 => Ok(PseudoColorLut::Sepia),
+            5 => Ok(PseudoColorLut::Color),
+            6 => Ok(PseudoColorLut::IceFire),
+            7 => Ok(PseudoColorLut::Rain),
+            8 => Ok(PseudoColorLut::User),
+            _ => Err(DecodeError::new("PseudoColorLut", value)),
+        }
+    }
+}
+
+impl From<PseudoColorLut> for u32 {
+    fn from(value: PseudoColorLut) -> Self {
+        value as u32
+    }
+}
+
+crate::lepton_cci::cci_words_via_u32!(Polarity, PseudoColorLut);
+
+/// Focus metric region of interest (`0x0310`), inclusive pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusRoi {
+    pub start_col: u16,
+    pub start_row: u16,
+    pub end_col: u16,
+    pub end_row: u16,
+}
+
+impl CciWords for FocusRoi {
+    const WORDS: usize = 4;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        Ok(FocusRoi {
+            start_col: words[0],
+            start_row: words[1],
+            end_col: words[2],
+            end_row: words[3],
+        })
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        words[0] = self.start_col;
+        words[1] = self.start_row;
+        words[2] = self.end_col;
+        words[3] = self.end_row;
+    }
+}
+
+/// One entry of a user pseudo-color LUT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LutPixel {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+/// 256-entry user pseudo-color LUT (`0x0308`), selected with [`PseudoColorLut::User`].
+///
+/// The LUT is 1024 bytes on the wire, so it is transferred through the CCI block data buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserLut(pub [LutPixel; 256]);
+
+impl Default for UserLut {
+    fn default() -> Self {
+        UserLut([LutPixel::default(); 256])
+    }
+}
+
+impl CciWords for UserLut {
+    const WORDS: usize = 512;
+
+    fn from_words(words: &[u16]) -> Result<Self, DecodeError> {
+        let mut lut = UserLut::default();
+        for (pixel, pair) in lut.0.iter_mut().zip(words.chunks_exact(2)) {
+            // Each entry is {reserved, red, green, blue} bytes in SDK memory order.
+            let [_, red] = pair[0].to_le_bytes();
+            let [green, blue] = pair[1].to_le_bytes();
+            *pixel = LutPixel { red, green, blue };
+        }
+        Ok(lut)
+    }
+
+    fn to_words(&self, words: &mut [u16]) {
+        for (pixel, pair) in self.0.iter().zip(words.chunks_exact_mut(2)) {
+            pair[0] = u16::from_le_bytes([0, pixel.red]);
+            pair[1] = u16::from_le_bytes([pixel.green, pixel.blue]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lepton_command::LepCommand;
+
+    #[test]
+    fn vid_commands_match_idd_ids() {
+        assert_eq!(LepCommand::get_vid_polarity().raw_command_id(), 0x0300);
+        assert_eq!(LepCommand::get_vid_lut_select().raw_command_id(), 0x0304);
+        assert_eq!(LepCommand::set_vid_user_lut().raw_command_id(), 0x0309);
+        assert_eq!(LepCommand::get_vid_focus_metric().raw_command_id(), 0x0318);
+        assert_eq!(LepCommand::get_vid_freeze().raw_command_id(), 0x0324);
+    }
+
+    #[test]
+    fn lut_round_trips_through_words() {
+        let mut words = [0u16; 2];
+        PseudoColorLut::IceFire.to_words(&mut words);
+        assert_eq!(words, [6, 0]);
+        assert_eq!(
+            PseudoColorLut::from_words(&words).unwrap(),
+            PseudoColorLut::IceFire
+        );
+    }
+
+    #[test]
+    fn polarity_rejects_unknown_value() {
+        assert_eq!(
+            Polarity::from_words(&[0, 1]).unwrap_err(),
+            DecodeError::new("Polarity", 0x1_0000)
+        );
+    }
+}
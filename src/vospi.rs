@@ -1,12 +1,19 @@
 use crate::crc::lepton_packet_crc16_spec;
+use crate::oem::VideoOutputFormat;
+use crate::sys::TelemetryMode;
+use crate::telemetry::{Telemetry, TELEMETRY_ROWS, TELEMETRY_ROW_BYTES};
 
 const PACKET_HEADER_BYTES: usize = 4;
 const DEFAULT_PACKET_SIZE_BYTES: usize = 164;
 const DEFAULT_LINES_PER_SEGMENT: usize = 60;
 const DEFAULT_SEGMENTS_PER_FRAME: usize = 4;
+const PIXELS_PER_PACKET: usize = 80;
+const RAW14_MASK: u16 = 0x3FFF;
 const PACKET_DISCARD_MASK: u16 = 0x0F00;
 const PACKET_NUMBER_MASK: u16 = 0x0FFF;
 const SEGMENT_BITS_MASK: u16 = 0x7;
+/// Shortest CS-deasserted, SCK-idle period after which the camera restarts VoSPI output.
+pub const MIN_RESYNC_IDLE_MS: u32 = 185;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncState {
@@ -61,17 +68,94 @@ pub fn probe_header(packet: &[u8]) -> Option<HeaderProbe> {
     })
 }
 
+/// Lepton family, which determines the VoSPI frame geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensorModel {
+    /// Lepton 2.x/2.5: 80x60, one 80-pixel line per packet, no segments.
+    Lepton2x,
+    /// Lepton 3.x/3.5: 160x120, sent as 4 segments of 60 half-line packets.
+    Lepton3x,
+}
+
+impl SensorModel {
+    pub fn width(self) -> usize {
+        match self {
+            SensorModel::Lepton2x => 80,
+            SensorModel::Lepton3x => 160,
+        }
+    }
+
+    pub fn height(self) -> usize {
+        match self {
+            SensorModel::Lepton2x => 60,
+            SensorModel::Lepton3x => 120,
+        }
+    }
+
+    pub fn segments_per_frame(self) -> usize {
+        match self {
+            SensorModel::Lepton2x => 1,
+            SensorModel::Lepton3x => DEFAULT_SEGMENTS_PER_FRAME,
+        }
+    }
+}
+
+/// Recovery applied when capture runs out of frame retries or resync attempts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResyncStrategy {
+    /// Only the `backoff_packet_reads` between attempts; the error that ended the last
+    /// attempt (or [`CaptureError::SyncLost`]) is returned to the caller.
+    #[default]
+    Backoff,
+    /// Deassert CS and idle SCK for `idle_ms` (at least [`MIN_RESYNC_IDLE_MS`]) through
+    /// [`PacketSource::resync_idle`], then start a fresh set of attempts. This is the
+    /// recovery documented for VoSPI; it runs at most once per capture call.
+    CsIdle { idle_ms: u32 },
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct RobustCaptureConfig {
     pub enable_crc: bool,
     pub max_resync_attempts: u32,
     pub max_frame_retries: u32,
     pub packet_size_bytes: usize,
+    /// Image lines (packets) per segment, excluding telemetry.
     pub lines_per_segment: usize,
+    /// Segments per frame; `1` selects an unsegmented (Lepton 2.x) stream, which carries no
+    /// segment ID on packet 20.
     pub segments_per_frame: usize,
+    /// Keep already-captured segments after an error and re-acquire only the missing ones.
+    ///
+    /// Segments are only combined if they belong to the same frame: with telemetry enabled
+    /// this is checked with the telemetry frame counter, otherwise all segments must be
+    /// captured within this many ticks of the first one. `0` disables segment retry, so any
+    /// error restarts the frame. Ignored by [`capture_segments_into`], which delivers
+    /// segments as soon as they arrive.
+    pub segment_retry_window_ticks: u64,
+    /// Place each segment by the ID in its packet 20 instead of requiring segments 1..N in
+    /// order, so capture can start mid-frame. Out-of-order arrivals are counted in
+    /// diagnostics rather than treated as errors. Segments are not checked to come from the
+    /// same frame unless segment retry is also enabled. Ignored by [`capture_segments_into`].
+    pub place_segments_by_id: bool,
+    /// Pixel format the camera was configured with; see [`RobustCaptureConfig::with_output_format`].
+    pub output_format: VideoOutputFormat,
+    /// Telemetry setting the camera was configured with (SYS telemetry enable/location).
+    ///
+    /// Telemetry packets are captured separately from the image and decoded into
+    /// [`FrameMeta::telemetry`].
+    pub telemetry: TelemetryMode,
     pub max_discard_packets: u32,
     pub timeout_packets: u32,
     pub backoff_packet_reads: u32,
+    pub resync_strategy: ResyncStrategy,
+    /// Packets fetched per [`PacketSource::read_packets`] call once packet 0 of a segment
+    /// has been accepted; the rest of the segment is then validated from memory. Reads stay
+    /// per packet while seeking sync. `0` or `1` always reads one packet at a time.
+    ///
+    /// The packet buffer passed to capture must hold this many packets
+    /// ([`required_packet_buffer_len`]); a smaller buffer limits the batch size. Packets
+    /// read ahead are dropped if capture returns an error part way through a batch.
+    pub bulk_read_packets: usize,
     /// Delay inserted after each packet read by the concrete `PacketSource` implementation.
     ///
     /// Keeping delay policy in `PacketSource::read_packet` ensures all robust-capture read paths
@@ -90,21 +174,60 @@ impl Default for RobustCaptureConfig {
             packet_size_bytes: DEFAULT_PACKET_SIZE_BYTES,
             lines_per_segment: DEFAULT_LINES_PER_SEGMENT,
             segments_per_frame: DEFAULT_SEGMENTS_PER_FRAME,
+            segment_retry_window_ticks: 0,
+            place_segments_by_id: false,
+            output_format: VideoOutputFormat::Raw14,
+            telemetry: TelemetryMode::Disabled,
             max_discard_packets: 600,
             timeout_packets: 3000,
             backoff_packet_reads: 2,
+            resync_strategy: ResyncStrategy::Backoff,
+            bulk_read_packets: 0,
             inter_packet_delay_us: 0,
             inter_packet_delay_discard_us: 0,
         }
     }
 }
 
+impl RobustCaptureConfig {
+    /// Default capture settings with the frame geometry of `model`.
+    pub fn for_sensor(model: SensorModel) -> Self {
+        Self {
+            lines_per_segment: DEFAULT_LINES_PER_SEGMENT,
+            segments_per_frame: model.segments_per_frame(),
+            ..Self::default()
+        }
+    }
+
+    /// Selects `format` and the matching packet size (244 bytes for RGB888, 164 otherwise).
+    pub fn with_output_format(self, format: VideoOutputFormat) -> Self {
+        Self {
+            output_format: format,
+            packet_size_bytes: packet_size_for_format(format),
+            ..self
+        }
+    }
+}
+
+/// VoSPI packet size, header included, for a stream in `format`.
+pub fn packet_size_for_format(format: VideoOutputFormat) -> usize {
+    PACKET_HEADER_BYTES + PIXELS_PER_PACKET * format.bytes_per_pixel()
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct FrameDiagnostics {
     pub discard_count: u32,
     pub crc_error_count: u32,
     pub bad_line_count: u32,
     pub resync_count: u32,
+    /// Segments abandoned while earlier segments of the frame were kept.
+    pub segment_retry_count: u32,
+    /// Segments that arrived in a different order than expected and were placed by ID.
+    pub out_of_order_segment_count: u32,
+    /// Segments the camera marked invalid (segment ID 0) and that were skipped.
+    pub invalid_segment_count: u32,
+    /// CS idle periods run by [`ResyncStrategy::CsIdle`].
+    pub cs_idle_count: u32,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -114,15 +237,56 @@ pub struct FrameMeta {
     pub discard_packets: u32,
     pub crc_errors: u32,
     pub bad_line_count: u32,
+    /// Failed attempts within this capture call before the frame was acquired.
     pub resync_count: u32,
+    pub segment_retries: u32,
+    pub out_of_order_segments: u32,
+    pub invalid_segments: u32,
+    /// Pixel format of the captured frame, from the capture config.
+    pub output_format: VideoOutputFormat,
+    /// Telemetry row A, when telemetry is enabled in the capture config.
+    pub telemetry: Option<Telemetry>,
 }
 
+/// A frame and its metadata.
+///
+/// Pixels are stored row-major in packet order, so pixel `index` is `row * width + col`.
 #[derive(Debug, Clone)]
 pub struct CapturedFrame {
     pub pixels: Vec<u8>,
     pub meta: FrameMeta,
 }
 
+impl CapturedFrame {
+    /// 14-bit pixel value at `index`, or `None` if out of range or not a RAW14 frame.
+    pub fn raw14_pixel(&self, index: usize) -> Option<u16> {
+        if self.meta.output_format != VideoOutputFormat::Raw14 {
+            return None;
+        }
+        let bytes = self.pixels.get(index * 2..index * 2 + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]) & RAW14_MASK)
+    }
+
+    /// `[red, green, blue]` pixel at `index`, or `None` if out of range or not an RGB888 frame.
+    pub fn rgb_pixel(&self, index: usize) -> Option<[u8; 3]> {
+        if self.meta.output_format != VideoOutputFormat::Rgb888 {
+            return None;
+        }
+        let bytes = self.pixels.get(index * 3..index * 3 + 3)?;
+        Some([bytes[0], bytes[1], bytes[2]])
+    }
+
+    /// Iterates the RGB888 pixels; empty if this is not an RGB888 frame.
+    pub fn rgb_pixels(&self) -> impl Iterator<Item = [u8; 3]> + '_ {
+        let pixels: &[u8] = if self.meta.output_format == VideoOutputFormat::Rgb888 {
+            &self.pixels
+        } else {
+            &[]
+        };
+        pixels.chunks_exact(3).map(|rgb| [rgb[0], rgb[1], rgb[2]])
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct PacketHeader {
     pub packet_id: u16,
@@ -184,8 +348,64 @@ pub fn validate_packet_crc(packet: &[u8]) -> bool {
 pub trait PacketSource {
     type Error;
     fn read_packet(&mut self, packet: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Fills `packets` with consecutive packets of `packet_size` bytes, for
+    /// [`RobustCaptureConfig::bulk_read_packets`].
+    ///
+    /// Sources with a high per-transfer cost should read them in one transfer; the default
+    /// calls [`read_packet`](Self::read_packet) for each.
+    fn read_packets(&mut self, packets: &mut [u8], packet_size: usize) -> Result<(), Self::Error> {
+        for packet in packets.chunks_exact_mut(packet_size) {
+            self.read_packet(packet)?;
+        }
+        Ok(())
+    }
+
+    /// Called before reading the first packet of each segment, and again after any resync.
+    ///
+    /// Sources wired to the camera's VSYNC output block here until the next segment is ready
+    /// instead of reading discard packets; the default starts reading immediately.
+    fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Holds CS deasserted with SCK idle for `idle_ms`, used by [`ResyncStrategy::CsIdle`].
+    ///
+    /// The default does nothing, for sources that cannot control the bus.
+    fn resync_idle(&mut self, _idle_ms: u32) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Telemetry packets added to every segment when telemetry is enabled.
+///
+/// The telemetry rows are spread evenly over the segments: a segmented Lepton 3.x frame
+/// grows by one packet per segment (61 packets, the fourth telemetry packet is reserved),
+/// while an unsegmented frame carries all three rows.
+pub fn telemetry_packets_per_segment(cfg: &RobustCaptureConfig) -> usize {
+    match cfg.telemetry {
+        TelemetryMode::Disabled => 0,
+        TelemetryMode::Header | TelemetryMode::Footer => {
+            TELEMETRY_ROWS.div_ceil(cfg.segments_per_frame.max(1))
+        }
+    }
 }
 
+/// Packets sent per segment, including telemetry.
+pub fn packets_per_segment(cfg: &RobustCaptureConfig) -> usize {
+    cfg.lines_per_segment + telemetry_packets_per_segment(cfg)
+}
+
+/// Length of the packet buffer for capture: one packet, or a batch of
+/// [`RobustCaptureConfig::bulk_read_packets`].
+pub fn required_packet_buffer_len(cfg: &RobustCaptureConfig) -> usize {
+    cfg.packet_size_bytes
+        * cfg
+            .bulk_read_packets
+            .clamp(1, packets_per_segment(cfg).max(1))
+}
+
+/// Length of the image buffer for one frame; telemetry lines are not included.
 pub fn required_frame_buffer_len(cfg: &RobustCaptureConfig) -> usize {
     if cfg.packet_size_bytes < PACKET_HEADER_BYTES {
         return 0;
@@ -200,14 +420,14 @@ pub fn capture_frame_from_source<S, F>(
     first_valid_synced: &mut bool,
     sync_state: &mut SyncState,
     diagnostics: &mut FrameDiagnostics,
-    mut now_ticks: F,
+    now_ticks: F,
 ) -> Result<CapturedFrame, CaptureError<S::Error>>
 where
     S: PacketSource,
     F: FnMut() -> u64,
 {
     let mut frame = vec![0; required_frame_buffer_len(cfg)];
-    let mut packet = vec![0; cfg.packet_size_bytes];
+    let mut packet = vec![0; required_packet_buffer_len(cfg)];
     let meta = capture_frame_into(
         source,
         cfg,
@@ -216,7 +436,7 @@ where
         diagnostics,
         &mut frame,
         &mut packet,
-        || now_ticks(),
+        now_ticks,
     )?;
 
     Ok(CapturedFrame {
@@ -225,6 +445,101 @@ where
     })
 }
 
+/// Length of the scratch buffer for [`capture_segments_into`]: one segment of image lines.
+pub fn required_segment_buffer_len(cfg: &RobustCaptureConfig) -> usize {
+    if cfg.packet_size_bytes < PACKET_HEADER_BYTES {
+        return 0;
+    }
+
+    (cfg.packet_size_bytes - PACKET_HEADER_BYTES) * cfg.lines_per_segment
+}
+
+/// An image segment delivered to a [`SegmentSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Zero-based segment position; the segment holds image lines
+    /// `index * lines .. (index + 1) * lines`.
+    pub index: usize,
+    pub lines: usize,
+    /// Whether the segment's packets were CRC-validated (`enable_crc`). A packet that fails
+    /// CRC abandons its segment, so a delivered segment with this set has passed in full.
+    pub crc_validated: bool,
+}
+
+/// Receives each image segment as soon as all of its lines have been validated.
+///
+/// Segments arrive in order. If capture has to resync part way through a frame, delivery
+/// starts again from index 0, so a frame is only complete once the capture call returns
+/// `Ok`.
+pub trait SegmentSink {
+    fn segment(&mut self, info: &SegmentInfo, pixels: &[u8]);
+}
+
+impl<F> SegmentSink for F
+where
+    F: FnMut(&SegmentInfo, &[u8]),
+{
+    fn segment(&mut self, info: &SegmentInfo, pixels: &[u8]) {
+        self(info, pixels)
+    }
+}
+
+/// Destination for validated image lines during capture.
+trait LineWriter {
+    fn write_line(&mut self, line: usize, payload: &[u8]);
+
+    /// Copies `count` already-written lines from `src` to `dst`; returns `false` if the
+    /// writer no longer holds them.
+    fn copy_lines(&mut self, _src: usize, _dst: usize, _count: usize, _line_len: usize) -> bool {
+        false
+    }
+}
+
+/// Whole frame held in memory.
+struct FrameWriter<'a>(&'a mut [u8]);
+
+impl LineWriter for FrameWriter<'_> {
+    fn write_line(&mut self, line: usize, payload: &[u8]) {
+        let dst_start = line * payload.len();
+        self.0[dst_start..dst_start + payload.len()].copy_from_slice(payload);
+    }
+
+    fn copy_lines(&mut self, src: usize, dst: usize, count: usize, line_len: usize) -> bool {
+        let src_start = src * line_len;
+        self.0
+            .copy_within(src_start..src_start + count * line_len, dst * line_len);
+        true
+    }
+}
+
+/// Assembles one segment at a time and hands it to a [`SegmentSink`].
+struct SegmentWriter<'a, K> {
+    buffer: &'a mut [u8],
+    sink: &'a mut K,
+    lines_per_segment: usize,
+    crc_validated: bool,
+}
+
+impl<K: SegmentSink> LineWriter for SegmentWriter<'_, K> {
+    fn write_line(&mut self, line: usize, payload: &[u8]) {
+        let row = line % self.lines_per_segment;
+        let dst_start = row * payload.len();
+        self.buffer[dst_start..dst_start + payload.len()].copy_from_slice(payload);
+
+        if row + 1 == self.lines_per_segment {
+            let info = SegmentInfo {
+                index: line / self.lines_per_segment,
+                lines: self.lines_per_segment,
+                crc_validated: self.crc_validated,
+            };
+            self.sink.segment(
+                &info,
+                &self.buffer[..self.lines_per_segment * payload.len()],
+            );
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn capture_frame_into<S, F>(
     source: &mut S,
@@ -234,7 +549,7 @@ pub fn capture_frame_into<S, F>(
     diagnostics: &mut FrameDiagnostics,
     frame: &mut [u8],
     packet_buf: &mut [u8],
-    mut now_ticks: F,
+    now_ticks: F,
 ) -> Result<FrameMeta, CaptureError<S::Error>>
 where
     S: PacketSource,
@@ -248,8 +563,92 @@ where
         return Err(CaptureError::InvalidPacket);
     }
 
+    capture_with_writer(
+        source,
+        cfg,
+        first_valid_synced,
+        sync_state,
+        diagnostics,
+        &mut FrameWriter(frame),
+        packet_buf,
+        now_ticks,
+    )
+}
+
+/// Captures one frame, handing each validated segment to `sink` instead of buffering the
+/// whole frame.
+///
+/// `segment_buf` needs [`required_segment_buffer_len`] bytes (9,600 for a RAW14 Lepton 3.x
+/// segment). Sync, CRC, ordering and retry handling are identical to [`capture_frame_into`].
+#[allow(clippy::too_many_arguments)]
+pub fn capture_segments_into<S, F, K>(
+    source: &mut S,
+    cfg: &RobustCaptureConfig,
+    first_valid_synced: &mut bool,
+    sync_state: &mut SyncState,
+    diagnostics: &mut FrameDiagnostics,
+    segment_buf: &mut [u8],
+    packet_buf: &mut [u8],
+    now_ticks: F,
+    sink: &mut K,
+) -> Result<FrameMeta, CaptureError<S::Error>>
+where
+    S: PacketSource,
+    F: FnMut() -> u64,
+    K: SegmentSink,
+{
+    if cfg.packet_size_bytes < PACKET_HEADER_BYTES || cfg.lines_per_segment == 0 {
+        return Err(CaptureError::InvalidPacket);
+    }
+
+    if packet_buf.len() < cfg.packet_size_bytes
+        || segment_buf.len() < required_segment_buffer_len(cfg)
+    {
+        return Err(CaptureError::InvalidPacket);
+    }
+
+    let cfg = &RobustCaptureConfig {
+        segment_retry_window_ticks: 0,
+        place_segments_by_id: false,
+        ..*cfg
+    };
+    let mut writer = SegmentWriter {
+        buffer: segment_buf,
+        sink,
+        lines_per_segment: cfg.lines_per_segment,
+        crc_validated: cfg.enable_crc,
+    };
+    capture_with_writer(
+        source,
+        cfg,
+        first_valid_synced,
+        sync_state,
+        diagnostics,
+        &mut writer,
+        packet_buf,
+        now_ticks,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn capture_with_writer<S, F, W>(
+    source: &mut S,
+    cfg: &RobustCaptureConfig,
+    first_valid_synced: &mut bool,
+    sync_state: &mut SyncState,
+    diagnostics: &mut FrameDiagnostics,
+    lines: &mut W,
+    packet_buf: &mut [u8],
+    mut now_ticks: F,
+) -> Result<FrameMeta, CaptureError<S::Error>>
+where
+    S: PacketSource,
+    F: FnMut() -> u64,
+    W: LineWriter,
+{
     let mut frame_attempts = 0u32;
     let mut resync_attempts = 0u32;
+    let mut cs_idled = false;
     let mut last_error: Option<CaptureError<S::Error>> = None;
 
     while frame_attempts <= cfg.max_frame_retries {
@@ -266,17 +665,20 @@ where
 
         let mut meta = FrameMeta {
             capture_ticks: now_ticks(),
+            resync_count: resync_attempts,
+            output_format: cfg.output_format,
             ..FrameMeta::default()
         };
 
         match read_one_frame(
             source,
             cfg,
-            frame,
+            lines,
             packet_buf,
             sync_state,
             diagnostics,
             &mut meta,
+            &mut now_ticks,
         ) {
             Ok(()) => {
                 *first_valid_synced = true;
@@ -296,7 +698,6 @@ where
                 diagnostics.resync_count += 1;
                 resync_attempts += 1;
                 frame_attempts += 1;
-                meta.resync_count += 1;
                 *sync_state = SyncState::Unsynced;
                 last_error = Some(err);
 
@@ -310,11 +711,26 @@ where
                         .map_err(CaptureError::Spi)?;
                 }
 
-                if resync_attempts > cfg.max_resync_attempts {
-                    return Err(CaptureError::SyncLost);
-                }
-
-                if frame_attempts > cfg.max_frame_retries {
+                let sync_lost = resync_attempts > cfg.max_resync_attempts;
+                if sync_lost || frame_attempts > cfg.max_frame_retries {
+                    if let (ResyncStrategy::CsIdle { idle_ms }, false) =
+                        (cfg.resync_strategy, cs_idled)
+                    {
+                        source
+                            .resync_idle(idle_ms.max(MIN_RESYNC_IDLE_MS))
+                            .map_err(CaptureError::Spi)?;
+                        diagnostics.cs_idle_count += 1;
+                        cs_idled = true;
+                        resync_attempts = 0;
+                        frame_attempts = 0;
+                        // The camera restarts its stream, so the next frame must be found
+                        // from segment 1 again.
+                        *first_valid_synced = false;
+                        continue;
+                    }
+                    if sync_lost {
+                        return Err(CaptureError::SyncLost);
+                    }
                     return Err(last_error.unwrap_or(CaptureError::RetryLimitExceeded));
                 }
 
@@ -326,36 +742,125 @@ where
     Err(last_error.unwrap_or(CaptureError::RetryLimitExceeded))
 }
 
-fn read_one_frame<S>(
+/// Progress through the segments of the frame being assembled.
+struct SegmentProgress {
+    /// Bit `n - 1` is set once segment `n` has been fully captured.
+    valid: u32,
+    expected_segment: usize,
+    expected_packet_number: usize,
+    segment_start: bool,
+    /// Whether lines of the current segment are stored; false for segments already captured.
+    writable: bool,
+    /// The current segment carries ID 0 and is read through without being kept.
+    invalid: bool,
+    first_valid_ticks: u64,
+    frame_counter: Option<u32>,
+}
+
+impl SegmentProgress {
+    fn new() -> Self {
+        Self {
+            valid: 0,
+            expected_segment: 1,
+            expected_packet_number: 0,
+            segment_start: true,
+            writable: true,
+            invalid: false,
+            first_valid_ticks: 0,
+            frame_counter: None,
+        }
+    }
+
+    fn segment_bit(segment: usize) -> u32 {
+        1 << (segment - 1)
+    }
+
+    /// Moves on to packet 0 of `segment`, keeping completed segments.
+    fn advance_to(&mut self, segment: usize) {
+        self.expected_segment = segment;
+        self.expected_packet_number = 0;
+        self.segment_start = true;
+        self.writable = self.valid & Self::segment_bit(segment) == 0;
+        self.invalid = false;
+    }
+
+    /// Abandons the current segment and waits for packet 0 of `next_segment`.
+    ///
+    /// Without segment retry, the whole frame restarts from segment 1.
+    fn restart(&mut self, retry: bool, next_segment: usize) {
+        if retry {
+            self.advance_to(next_segment);
+        } else {
+            self.valid = 0;
+            self.frame_counter = None;
+            self.advance_to(1);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_one_frame<S, W, F>(
     source: &mut S,
     cfg: &RobustCaptureConfig,
-    frame: &mut [u8],
+    lines: &mut W,
     packet_buf: &mut [u8],
     sync_state: &mut SyncState,
     diagnostics: &mut FrameDiagnostics,
     meta: &mut FrameMeta,
+    now_ticks: &mut F,
 ) -> Result<(), CaptureError<S::Error>>
 where
     S: PacketSource,
+    W: LineWriter,
+    F: FnMut() -> u64,
 {
     if cfg.packet_size_bytes < PACKET_HEADER_BYTES {
         return Err(CaptureError::InvalidPacket);
     }
 
+    let packets_per_segment = packets_per_segment(cfg);
+    let telemetry_packets = telemetry_packets_per_segment(cfg) * cfg.segments_per_frame;
+    let image_packets = cfg.lines_per_segment * cfg.segments_per_frame;
+    let all_segments = (1u32 << cfg.segments_per_frame) - 1;
     let payload_len = cfg.packet_size_bytes - PACKET_HEADER_BYTES;
-    let mut expected_segment = 1usize;
-    let mut expected_packet_number = 0usize;
+    let next_segment = |segment: usize| segment % cfg.segments_per_frame + 1;
+    // Image line carried by `packet` of `segment`, or `None` for a telemetry packet.
+    let image_line = |segment: usize, packet: usize| {
+        let frame_packet = (segment - 1) * packets_per_segment + packet;
+        match cfg.telemetry {
+            TelemetryMode::Disabled => Some(frame_packet),
+            TelemetryMode::Header => frame_packet.checked_sub(telemetry_packets),
+            TelemetryMode::Footer => (frame_packet < image_packets).then_some(frame_packet),
+        }
+    };
+    let retry = cfg.segment_retry_window_ticks > 0 && cfg.segments_per_frame > 1;
+    let by_id = cfg.place_segments_by_id && cfg.segments_per_frame > 1;
+    let mut telemetry_row_a = [0u8; TELEMETRY_ROW_BYTES];
+    let mut progress = SegmentProgress::new();
     let mut packets_seen = 0u32;
     let locked = *sync_state == SyncState::Locked;
+    let mut batch = PacketBatch::new(cfg, packet_buf);
+
+    while progress.valid != all_segments {
+        if progress.segment_start {
+            // Packets still buffered were read after the previous segment; the stream has
+            // already moved on, so there is nothing to wait for.
+            if batch.is_empty() {
+                source.wait_segment_start().map_err(CaptureError::Spi)?;
+            }
+            progress.segment_start = false;
+        }
 
-    while expected_segment <= cfg.segments_per_frame {
-        source
-            .read_packet(&mut packet_buf[..cfg.packet_size_bytes])
-            .map_err(CaptureError::Spi)?;
+        // Once a segment is under way, fetch the rest of it in one transfer.
+        let remaining = match progress.expected_packet_number {
+            0 => 1,
+            n => packets_per_segment - n,
+        };
+        let packet = batch.next(source, remaining).map_err(CaptureError::Spi)?;
         packets_seen += 1;
         // every 64 packets, log a 10 line header probe sample for diagnostics, up to 300 packets (10 samples)
         // if packets_seen <= 1000 {
-        //     if let Some(p) = probe_header(&packet_buf[..cfg.packet_size_bytes]) {
+        //     if let Some(p) = probe_header(&packet) {
         //         if !p.is_discard_be {
         //             log::warn!(
         //     "hdr: b0={:02X} b1={:02X} id_be={:04X} id_le={:04X} pn_be={} disc_be={} disc_le={} seg20_be={:?}",
@@ -369,8 +874,7 @@ where
             return Err(CaptureError::Timeout);
         }
 
-        let header = parse_packet_header(&packet_buf[..cfg.packet_size_bytes])
-            .ok_or(CaptureError::InvalidPacket)?;
+        let header = parse_packet_header(packet).ok_or(CaptureError::InvalidPacket)?;
 
         if header.is_discard {
             diagnostics.discard_count += 1;
@@ -379,90 +883,259 @@ where
                 return Err(CaptureError::DiscardPacketFlood);
             }
             for _ in 0..cfg.backoff_packet_reads {
-                source
-                    .read_packet(&mut packet_buf[..cfg.packet_size_bytes])
-                    .map_err(CaptureError::Spi)?;
+                batch.next(source, 1).map_err(CaptureError::Spi)?;
             }
 
             continue;
         }
 
-        if cfg.enable_crc && !validate_packet_crc(&packet_buf[..cfg.packet_size_bytes]) {
+        if cfg.enable_crc && !validate_packet_crc(packet) {
             diagnostics.crc_error_count += 1;
             meta.crc_errors += 1;
-            if locked {
+            if locked && !retry {
                 return Err(CaptureError::CrcMismatch);
             }
-            expected_segment = 1;
-            expected_packet_number = 0;
+            abandon_segment(&mut progress, cfg, retry, diagnostics, meta);
             continue;
         }
 
         let packet_number = header.packet_number as usize;
 
-        if !locked && expected_segment == 1 && expected_packet_number == 0 && packet_number != 0 {
+        let seeking = retry || by_id || (!locked && progress.expected_segment == 1);
+        if seeking && progress.expected_packet_number == 0 && packet_number != 0 {
             continue;
         }
 
-        if packet_number != expected_packet_number {
+        if packet_number != progress.expected_packet_number {
             if locked {
                 diagnostics.bad_line_count += 1;
                 meta.bad_line_count += 1;
-                return Err(CaptureError::LineOutOfOrder {
-                    expected: expected_packet_number as u16,
-                    observed: header.packet_number,
-                });
+                if !retry {
+                    return Err(CaptureError::LineOutOfOrder {
+                        expected: progress.expected_packet_number as u16,
+                        observed: header.packet_number,
+                    });
+                }
             }
 
-            expected_segment = 1;
-            expected_packet_number = 0;
+            abandon_segment(&mut progress, cfg, retry, diagnostics, meta);
             continue;
         }
 
-        if packet_number == 20 {
+        if packet_number == 20 && cfg.segments_per_frame > 1 {
             let segment = header
                 .decode_segment_on_packet20()
                 .ok_or(CaptureError::InvalidPacket)?;
-            if segment == 0 || segment as usize > cfg.segments_per_frame {
-                if locked {
+            if segment == 0 {
+                // Not a framing error: the camera sends a whole segment to discard and then
+                // continues with the segment that was expected.
+                diagnostics.invalid_segment_count += 1;
+                meta.invalid_segments += 1;
+                progress.writable = false;
+                progress.invalid = true;
+            } else if segment as usize > cfg.segments_per_frame {
+                if locked && !retry {
                     return Err(CaptureError::InvalidPacket);
                 }
-                expected_segment = 1;
-                expected_packet_number = 0;
+                abandon_segment(&mut progress, cfg, retry, diagnostics, meta);
                 continue;
-            }
-
-            if segment as usize != expected_segment {
-                if locked {
+            } else if segment as usize != progress.expected_segment && by_id {
+                diagnostics.out_of_order_segment_count += 1;
+                meta.out_of_order_segments += 1;
+                let observed = segment as usize;
+                let observed_missing = progress.valid & SegmentProgress::segment_bit(observed) == 0;
+                // Packets 0..20 were stored as the predicted segment; move them if both
+                // segments start with image lines (header telemetry occupies segment 1).
+                let relocation = match (
+                    image_line(progress.expected_segment, 0),
+                    image_line(observed, 0),
+                    image_line(progress.expected_segment, 19),
+                    image_line(observed, 19),
+                ) {
+                    (Some(src), Some(dst), Some(_), Some(_)) => Some((src, dst)),
+                    _ => None,
+                };
+                let moved = progress.writable
+                    && observed_missing
+                    && relocation
+                        .is_some_and(|(src, dst)| lines.copy_lines(src, dst, 20, payload_len));
+                progress.expected_segment = observed;
+                progress.writable = moved;
+            } else if segment as usize != progress.expected_segment {
+                if locked && !retry {
                     return Err(CaptureError::SegmentOutOfOrder {
-                        expected: expected_segment as u8,
+                        expected: progress.expected_segment as u8,
                         observed: segment,
                     });
                 }
 
-                expected_segment = 1;
-                expected_packet_number = 0;
+                // The first lines went to the predicted segment, so this one is lost too;
+                // the stream continues with the segment after it.
+                let next = next_segment(segment as usize);
+                restart_segment(&mut progress, retry, next, diagnostics, meta);
                 continue;
             }
         }
 
-        let frame_line = (expected_segment - 1) * cfg.lines_per_segment + expected_packet_number;
-        let dst_start = frame_line * payload_len;
-        let dst_end = dst_start + payload_len;
-        frame[dst_start..dst_end]
-            .copy_from_slice(&packet_buf[PACKET_HEADER_BYTES..cfg.packet_size_bytes]);
+        let payload = &packet[PACKET_HEADER_BYTES..];
+        let frame_line = image_line(progress.expected_segment, progress.expected_packet_number);
+        let telemetry_row = match (frame_line, cfg.telemetry) {
+            (Some(_), _) | (None, TelemetryMode::Disabled) => None,
+            (None, TelemetryMode::Header) => Some(
+                (progress.expected_segment - 1) * packets_per_segment
+                    + progress.expected_packet_number,
+            ),
+            (None, TelemetryMode::Footer) => Some(
+                (progress.expected_segment - 1) * packets_per_segment
+                    + progress.expected_packet_number
+                    - image_packets,
+            ),
+        };
+
+        match telemetry_row {
+            Some(0) => {
+                let frame_counter = Telemetry::from_row_a(payload).map(|t| t.frame_counter);
+                let new_frame = matches!(
+                    (progress.frame_counter, frame_counter),
+                    (Some(kept), Some(seen)) if kept != seen
+                );
+                if retry && progress.valid != 0 && new_frame {
+                    // Kept segments belong to an older frame; lines of this segment that were
+                    // skipped before the counter was seen cannot be recovered.
+                    progress.valid = 0;
+                    progress.writable = progress.expected_packet_number == 0;
+                }
+                if progress.writable {
+                    let len = payload.len().min(TELEMETRY_ROW_BYTES);
+                    telemetry_row_a[..len].copy_from_slice(&payload[..len]);
+                    progress.frame_counter = frame_counter;
+                }
+            }
+            Some(_) => {}
+            None => {
+                if let (Some(line), true) = (frame_line, progress.writable) {
+                    lines.write_line(line, payload);
+                }
+            }
+        }
+
+        progress.expected_packet_number += 1;
+        if progress.expected_packet_number == packets_per_segment {
+            if progress.writable {
+                let now = now_ticks();
+                // Timing only decides when no frame counter has been seen yet.
+                let stale = progress.frame_counter.is_none()
+                    && now.saturating_sub(progress.first_valid_ticks)
+                        > cfg.segment_retry_window_ticks;
+                if retry && progress.valid != 0 && stale {
+                    progress.valid = 0;
+                }
+                if progress.valid == 0 {
+                    progress.first_valid_ticks = now;
+                }
+                progress.valid |= SegmentProgress::segment_bit(progress.expected_segment);
+            }
 
-        expected_packet_number += 1;
-        if expected_packet_number == cfg.lines_per_segment {
-            expected_packet_number = 0;
-            expected_segment += 1;
+            let segment = if progress.invalid {
+                progress.expected_segment
+            } else {
+                next_segment(progress.expected_segment)
+            };
+            progress.advance_to(segment);
         }
     }
 
+    if cfg.telemetry != TelemetryMode::Disabled {
+        meta.telemetry = Telemetry::from_row_a(&telemetry_row_a);
+    }
+
     *sync_state = SyncState::Locked;
     Ok(())
 }
 
+/// Packets read ahead in bulk and handed to `read_one_frame` one at a time, so the stream
+/// is consumed in the same order whatever the batch size.
+struct PacketBatch<'a> {
+    buffer: &'a mut [u8],
+    packet_size: usize,
+    capacity: usize,
+    next: usize,
+    len: usize,
+}
+
+impl<'a> PacketBatch<'a> {
+    fn new(cfg: &RobustCaptureConfig, buffer: &'a mut [u8]) -> Self {
+        let capacity = cfg
+            .bulk_read_packets
+            .min(buffer.len() / cfg.packet_size_bytes)
+            .max(1);
+        Self {
+            buffer,
+            packet_size: cfg.packet_size_bytes,
+            capacity,
+            next: 0,
+            len: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.next == self.len
+    }
+
+    /// Next packet of the stream; when none is buffered, reads up to `wanted` packets.
+    fn next<S: PacketSource>(&mut self, source: &mut S, wanted: usize) -> Result<&[u8], S::Error> {
+        if self.is_empty() {
+            let count = wanted.clamp(1, self.capacity);
+            let packets = &mut self.buffer[..count * self.packet_size];
+            if count == 1 {
+                source.read_packet(packets)?;
+            } else {
+                source.read_packets(packets, self.packet_size)?;
+            }
+            self.next = 0;
+            self.len = count;
+        }
+
+        let start = self.next * self.packet_size;
+        self.next += 1;
+        Ok(&self.buffer[start..start + self.packet_size])
+    }
+}
+
+/// Drops the segment in progress after a bad packet, keeping completed segments when
+/// segment retry is enabled.
+///
+/// If the bad packet arrived mid-segment, the rest of that segment is lost and the next
+/// packet 0 belongs to the following segment.
+fn abandon_segment(
+    progress: &mut SegmentProgress,
+    cfg: &RobustCaptureConfig,
+    retry: bool,
+    diagnostics: &mut FrameDiagnostics,
+    meta: &mut FrameMeta,
+) {
+    let next_segment = if progress.expected_packet_number == 0 {
+        progress.expected_segment
+    } else {
+        progress.expected_segment % cfg.segments_per_frame + 1
+    };
+    restart_segment(progress, retry, next_segment, diagnostics, meta);
+}
+
+fn restart_segment(
+    progress: &mut SegmentProgress,
+    retry: bool,
+    next_segment: usize,
+    diagnostics: &mut FrameDiagnostics,
+    meta: &mut FrameMeta,
+) {
+    if retry && progress.valid != 0 {
+        diagnostics.segment_retry_count += 1;
+        meta.segment_retries += 1;
+    }
+    progress.restart(retry, next_segment);
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum CaptureError<SpiError> {
     Spi(SpiError),
@@ -487,6 +1160,11 @@ mod tests {
     struct MockPacketSource {
         packets: Vec<Vec<u8>>,
         idx: usize,
+        segment_waits: usize,
+        idles: Vec<u32>,
+        bulk_reads: Vec<usize>,
+        /// Packet index the stream continues from after a resync idle.
+        restart_at: Option<usize>,
     }
 
     impl PacketSource for MockPacketSource {
@@ -500,6 +1178,31 @@ mod tests {
             self.idx += 1;
             Ok(())
         }
+
+        fn read_packets(
+            &mut self,
+            packets: &mut [u8],
+            packet_size: usize,
+        ) -> Result<(), Self::Error> {
+            self.bulk_reads.push(packets.len() / packet_size);
+            for packet in packets.chunks_exact_mut(packet_size) {
+                self.read_packet(packet)?;
+            }
+            Ok(())
+        }
+
+        fn wait_segment_start(&mut self) -> Result<(), Self::Error> {
+            self.segment_waits += 1;
+            Ok(())
+        }
+
+        fn resync_idle(&mut self, idle_ms: u32) -> Result<(), Self::Error> {
+            self.idles.push(idle_ms);
+            if let Some(idx) = self.restart_at {
+                self.idx = idx;
+            }
+            Ok(())
+        }
     }
 
     fn mk_packet(
@@ -508,7 +1211,23 @@ mod tests {
         payload_seed: u8,
         discard_id: Option<u16>,
     ) -> Vec<u8> {
-        let mut packet = vec![0u8; DEFAULT_PACKET_SIZE_BYTES];
+        mk_packet_sized(
+            DEFAULT_PACKET_SIZE_BYTES,
+            packet_number,
+            segment,
+            payload_seed,
+            discard_id,
+        )
+    }
+
+    fn mk_packet_sized(
+        packet_size: usize,
+        packet_number: u16,
+        segment: u8,
+        payload_seed: u8,
+        discard_id: Option<u16>,
+    ) -> Vec<u8> {
+        let mut packet = vec![0u8; packet_size];
         if let Some(id) = discard_id {
             packet[0..2].copy_from_slice(&id.to_be_bytes());
         } else {
@@ -530,15 +1249,29 @@ mod tests {
     }
 
     fn mk_frame() -> Vec<Vec<u8>> {
+        mk_frame_with_packets(DEFAULT_LINES_PER_SEGMENT as u16)
+    }
+
+    fn mk_frame_with_packets(packets_per_segment: u16) -> Vec<Vec<u8>> {
         let mut packets = Vec::new();
         for segment in 1..=(DEFAULT_SEGMENTS_PER_FRAME as u8) {
-            for packet_number in 0..(DEFAULT_LINES_PER_SEGMENT as u16) {
-                packets.push(mk_packet(packet_number, segment, (segment * 9) as u8, None));
+            for packet_number in 0..packets_per_segment {
+                packets.push(mk_packet(packet_number, segment, segment * 9, None));
             }
         }
         packets
     }
 
+    /// Turns `packet` into telemetry row A reporting `frame_counter`.
+    fn set_telemetry_row_a(packet: &mut [u8], frame_counter: u32) {
+        packet[PACKET_HEADER_BYTES..].fill(0);
+        let counter_offset = PACKET_HEADER_BYTES + 20 * 2;
+        packet[counter_offset..counter_offset + 2]
+            .copy_from_slice(&(frame_counter as u16).to_be_bytes());
+        packet[counter_offset + 2..counter_offset + 4]
+            .copy_from_slice(&((frame_counter >> 16) as u16).to_be_bytes());
+    }
+
     fn run_capture(
         source: &mut MockPacketSource,
         cfg: &RobustCaptureConfig,
@@ -587,15 +1320,73 @@ mod tests {
     }
 
     #[test]
-    fn segment_zero_on_packet_20_rejected() {
-        let mut packets = mk_frame();
-        packets[20] = mk_packet(20, 0, 0, None);
-        let mut source = MockPacketSource { packets, idx: 0 };
-        let mut cfg = RobustCaptureConfig::default();
-        cfg.max_frame_retries = 0;
+    fn bulk_reads_fetch_rest_of_each_segment_after_packet_zero() {
+        let discard = mk_packet(0, 0, 0, Some(0x0F00));
+        let mut packets = vec![discard.clone(), discard];
+        packets.extend(mk_frame());
+        let single_cfg = RobustCaptureConfig {
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+        let cfg = RobustCaptureConfig {
+            bulk_read_packets: 64,
+            ..single_cfg
+        };
+        assert_eq!(
+            required_packet_buffer_len(&cfg),
+            60 * DEFAULT_PACKET_SIZE_BYTES
+        );
 
-        let err = run_capture_locked(&mut source, &cfg).unwrap_err();
-        assert_eq!(err, CaptureError::InvalidPacket);
+        let mut single = MockPacketSource {
+            packets: packets.clone(),
+            ..MockPacketSource::default()
+        };
+        let mut bulk = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let expected = run_capture(&mut single, &single_cfg).unwrap();
+        let captured = run_capture(&mut bulk, &cfg).unwrap();
+
+        assert_eq!(captured.pixels, expected.pixels);
+        assert_eq!(captured.meta.discard_packets, 2);
+        assert!(single.bulk_reads.is_empty());
+        assert_eq!(bulk.bulk_reads, vec![59; DEFAULT_SEGMENTS_PER_FRAME]);
+        assert_eq!(bulk.idx, bulk.packets.len());
+    }
+
+    #[test]
+    fn segment_zero_on_packet_20_is_skipped_without_resync() {
+        let frame = mk_frame();
+        // A full segment numbered 0 arrives before segment 2.
+        let invalid: Vec<Vec<u8>> = (0..60).map(|line| mk_packet(line, 0, 0xEE, None)).collect();
+        let packets: Vec<Vec<u8>> = frame[..60]
+            .iter()
+            .chain(&invalid)
+            .chain(&frame[60..])
+            .cloned()
+            .collect();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            max_frame_retries: 0,
+            max_resync_attempts: 0,
+            ..RobustCaptureConfig::default()
+        };
+
+        let captured = run_capture_locked(&mut source, &cfg).unwrap();
+        assert_eq!(captured.meta.invalid_segments, 1);
+        assert_eq!(captured.meta.resync_count, 0);
+        assert_eq!(source.idx, 300);
+        for (line, packet) in frame.iter().enumerate() {
+            let start = line * DEFAULT_PAYLOAD_BYTES_PER_PACKET;
+            assert_eq!(
+                &captured.pixels[start..start + DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+                &packet[PACKET_HEADER_BYTES..]
+            );
+        }
     }
 
     #[test]
@@ -611,7 +1402,10 @@ mod tests {
         let mut packets = vec![mk_packet(11, 1, 0, None), mk_packet(8, 2, 0, None)];
         packets.extend(mk_frame());
 
-        let mut source = MockPacketSource { packets, idx: 0 };
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
         let cfg = RobustCaptureConfig::default();
 
         let frame = run_capture(&mut source, &cfg).unwrap();
@@ -620,10 +1414,14 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn wrong_segment_order_rejected_when_locked() {
         let mut packets = mk_frame();
         packets[60 + 20] = mk_packet(20, 3, 0, None);
-        let mut source = MockPacketSource { packets, idx: 0 };
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
         let mut cfg = RobustCaptureConfig::default();
         cfg.max_frame_retries = 0;
 
@@ -638,10 +1436,14 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn line_jump_rejected_when_locked() {
         let mut packets = mk_frame();
         packets[8] = mk_packet(11, 1, 0, None);
-        let mut source = MockPacketSource { packets, idx: 0 };
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
         let mut cfg = RobustCaptureConfig::default();
         cfg.max_frame_retries = 0;
 
@@ -656,9 +1458,33 @@ mod tests {
     }
 
     #[test]
+    fn meta_reports_failed_attempts_before_the_frame() {
+        let discard = mk_packet(0, 0, 0, Some(0x0F00));
+        let mut packets = vec![discard.clone(), discard];
+        packets.extend(mk_frame());
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            max_discard_packets: 1,
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+
+        let captured = run_capture(&mut source, &cfg).unwrap();
+        assert_eq!(captured.meta.resync_count, 1);
+        assert_eq!(captured.meta.discard_packets, 0);
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn retries_and_resync_are_bounded() {
         let packets = vec![mk_packet(0, 1, 0, Some(0xF123)); 200];
-        let mut source = MockPacketSource { packets, idx: 0 };
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
         let mut cfg = RobustCaptureConfig::default();
         cfg.max_frame_retries = 2;
         cfg.max_resync_attempts = 1;
@@ -688,7 +1514,10 @@ mod tests {
     #[test]
     fn capture_ticks_uses_supplied_tick_source() {
         let packets = mk_frame();
-        let mut source = MockPacketSource { packets, idx: 0 };
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
         let cfg = RobustCaptureConfig::default();
         let mut synced = false;
         let mut state = SyncState::Unsynced;
@@ -707,4 +1536,276 @@ mod tests {
         assert_eq!(frame.meta.capture_ticks, 123);
         assert!(frame.meta.valid);
     }
+
+    #[test]
+    fn header_telemetry_is_separated_from_image() {
+        let mut packets = mk_frame_with_packets(61);
+        set_telemetry_row_a(&mut packets[0], 0x0001_0010);
+        let first_image_payload = packets[4][PACKET_HEADER_BYTES..].to_vec();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            telemetry: TelemetryMode::Header,
+            ..RobustCaptureConfig::default()
+        };
+
+        let frame = run_capture(&mut source, &cfg).unwrap();
+        assert_eq!(frame.pixels.len(), 160 * 60 * 4);
+        assert_eq!(
+            &frame.pixels[..DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+            &first_image_payload[..]
+        );
+        assert_eq!(frame.meta.telemetry.unwrap().frame_counter, 0x0001_0010);
+    }
+
+    #[test]
+    fn footer_telemetry_follows_last_image_line() {
+        let mut packets = mk_frame_with_packets(61);
+        // Segment 4 packets 57..60 carry the telemetry rows.
+        set_telemetry_row_a(&mut packets[3 * 61 + 57], 42);
+        let last_image_payload = packets[3 * 61 + 56][PACKET_HEADER_BYTES..].to_vec();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            telemetry: TelemetryMode::Footer,
+            ..RobustCaptureConfig::default()
+        };
+
+        let frame = run_capture(&mut source, &cfg).unwrap();
+        assert_eq!(
+            &frame.pixels[frame.pixels.len() - DEFAULT_PAYLOAD_BYTES_PER_PACKET..],
+            &last_image_payload[..]
+        );
+        assert_eq!(frame.meta.telemetry.unwrap().frame_counter, 42);
+    }
+
+    #[test]
+    fn unsegmented_lepton2_frame_ignores_segment_bits() {
+        // Lepton 2.x leaves the segment bits of packet 20 clear.
+        let packets = (0..60)
+            .map(|packet_number| mk_packet(packet_number, 0, 3, None))
+            .collect();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            enable_crc: true,
+            ..RobustCaptureConfig::for_sensor(SensorModel::Lepton2x)
+        };
+
+        let frame = run_capture(&mut source, &cfg).unwrap();
+        assert!(frame.meta.valid);
+        assert_eq!(frame.pixels.len(), 80 * 60 * 2);
+    }
+
+    #[test]
+    fn rgb888_frames_use_244_byte_packets() {
+        let cfg = RobustCaptureConfig::default().with_output_format(VideoOutputFormat::Rgb888);
+        assert_eq!(cfg.packet_size_bytes, 244);
+
+        let mut packets = Vec::new();
+        for segment in 1..=4u8 {
+            for packet_number in 0..60 {
+                packets.push(mk_packet_sized(244, packet_number, segment, 0, None));
+            }
+        }
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+
+        let frame = run_capture(&mut source, &cfg).unwrap();
+        assert_eq!(frame.pixels.len(), 160 * 120 * 3);
+        assert_eq!(frame.rgb_pixel(1), Some([3, 4, 5]));
+        assert_eq!(frame.rgb_pixels().count(), 160 * 120);
+        assert_eq!(frame.raw14_pixel(0), None);
+    }
+
+    #[test]
+    fn segment_start_is_awaited_once_per_segment() {
+        let mut source = MockPacketSource {
+            packets: mk_frame(),
+            ..MockPacketSource::default()
+        };
+
+        run_capture(&mut source, &RobustCaptureConfig::default()).unwrap();
+        assert_eq!(source.segment_waits, DEFAULT_SEGMENTS_PER_FRAME);
+    }
+
+    #[test]
+    fn segments_stream_through_single_segment_buffer() {
+        let packets = mk_frame();
+        let expected_line_60 = packets[60][PACKET_HEADER_BYTES..].to_vec();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig::default();
+        let mut segment_buf = vec![0; required_segment_buffer_len(&cfg)];
+        let mut packet_buf = vec![0; cfg.packet_size_bytes];
+        let mut seen = Vec::new();
+        let mut sink = |info: &SegmentInfo, pixels: &[u8]| {
+            if info.index == 1 {
+                assert_eq!(
+                    &pixels[..DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+                    &expected_line_60[..]
+                );
+            }
+            seen.push((info.index, pixels.len()));
+        };
+
+        let meta = capture_segments_into(
+            &mut source,
+            &cfg,
+            &mut false,
+            &mut SyncState::Unsynced,
+            &mut FrameDiagnostics::default(),
+            &mut segment_buf,
+            &mut packet_buf,
+            || 1,
+            &mut sink,
+        )
+        .unwrap();
+
+        assert!(meta.valid);
+        assert_eq!(segment_buf.len(), 9600);
+        assert_eq!(seen, vec![(0, 9600), (1, 9600), (2, 9600), (3, 9600)]);
+    }
+
+    #[test]
+    fn segment_retry_keeps_good_segments() {
+        let first = mk_frame();
+        let mut second: Vec<Vec<u8>> = (1..=4u8)
+            .flat_map(|segment| (0..60).map(move |n| mk_packet(n, segment, segment * 7, None)))
+            .collect();
+        let mut packets = first.clone();
+        packets[2 * 60 + 30][40] ^= 0xFF;
+        packets.append(&mut second);
+        let expected_segment_3 = packets[4 * 60 + 2 * 60][PACKET_HEADER_BYTES..].to_vec();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            enable_crc: true,
+            segment_retry_window_ticks: 100,
+            ..RobustCaptureConfig::default()
+        };
+
+        let frame = run_capture_locked(&mut source, &cfg).unwrap();
+        assert_eq!(frame.meta.segment_retries, 1);
+        assert_eq!(frame.meta.crc_errors, 1);
+        let segment_3_start = 2 * 60 * DEFAULT_PAYLOAD_BYTES_PER_PACKET;
+        assert_eq!(
+            &frame.pixels[segment_3_start..segment_3_start + DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+            &expected_segment_3[..]
+        );
+        assert_eq!(
+            &frame.pixels[..DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+            &first[0][PACKET_HEADER_BYTES..]
+        );
+        // Segments 1 and 2 of the second frame were skipped; segment 4 came from the first.
+        assert_eq!(source.idx, 4 * 60 + 3 * 60);
+    }
+
+    #[test]
+    fn segments_are_placed_by_id_when_starting_mid_frame() {
+        let frame = mk_frame();
+        // Start reading at segment 3; the frame completes with the next 1 and 2.
+        let packets: Vec<Vec<u8>> = frame[120..].iter().chain(&frame[..120]).cloned().collect();
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            place_segments_by_id: true,
+            ..RobustCaptureConfig::default()
+        };
+
+        let captured = run_capture_locked(&mut source, &cfg).unwrap();
+        assert_eq!(captured.meta.out_of_order_segments, 1);
+        assert_eq!(source.idx, 240);
+        for (line, packet) in frame.iter().enumerate() {
+            let start = line * DEFAULT_PAYLOAD_BYTES_PER_PACKET;
+            assert_eq!(
+                &captured.pixels[start..start + DEFAULT_PAYLOAD_BYTES_PER_PACKET],
+                &packet[PACKET_HEADER_BYTES..]
+            );
+        }
+    }
+
+    #[test]
+    fn cs_idle_resync_recovers_from_sync_loss() {
+        let discard = mk_packet(0, 0, 0, Some(0x0F00));
+        let packets: Vec<Vec<u8>> = std::iter::repeat_n(discard, 3).chain(mk_frame()).collect();
+        let cfg = RobustCaptureConfig {
+            max_discard_packets: 2,
+            max_resync_attempts: 0,
+            backoff_packet_reads: 0,
+            ..RobustCaptureConfig::default()
+        };
+
+        let mut source = MockPacketSource {
+            packets: packets.clone(),
+            ..MockPacketSource::default()
+        };
+        let err = run_capture_locked(&mut source, &cfg).unwrap_err();
+        assert_eq!(err, CaptureError::SyncLost);
+        assert!(source.idles.is_empty());
+
+        let mut source = MockPacketSource {
+            packets,
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            resync_strategy: ResyncStrategy::CsIdle { idle_ms: 100 },
+            ..cfg
+        };
+        let captured = run_capture_locked(&mut source, &cfg).unwrap();
+        assert!(captured.meta.valid);
+        assert_eq!(source.idles, vec![MIN_RESYNC_IDLE_MS]);
+    }
+
+    #[test]
+    fn cs_idle_runs_when_frame_retries_run_out_at_default_limits() {
+        let discard = mk_packet(0, 0, 0, Some(0x0F00));
+        let flood = 20_000;
+        let packets: Vec<Vec<u8>> = std::iter::repeat_n(discard, flood)
+            .chain(mk_frame())
+            .collect();
+
+        let mut source = MockPacketSource {
+            packets: packets.clone(),
+            restart_at: Some(flood),
+            ..MockPacketSource::default()
+        };
+        let err = run_capture_locked(&mut source, &RobustCaptureConfig::default()).unwrap_err();
+        assert_eq!(err, CaptureError::DiscardPacketFlood);
+        assert!(source.idles.is_empty());
+
+        let mut source = MockPacketSource {
+            packets,
+            restart_at: Some(flood),
+            ..MockPacketSource::default()
+        };
+        let cfg = RobustCaptureConfig {
+            resync_strategy: ResyncStrategy::CsIdle { idle_ms: 200 },
+            ..RobustCaptureConfig::default()
+        };
+        let mut synced = true;
+        let mut state = SyncState::Locked;
+        let mut diag = FrameDiagnostics::default();
+        let captured =
+            capture_frame_from_source(&mut source, &cfg, &mut synced, &mut state, &mut diag, || 1)
+                .unwrap();
+        assert!(captured.meta.valid);
+        assert_eq!(source.idles, vec![200]);
+        assert_eq!(diag.cs_idle_count, 1);
+        assert_eq!(diag.resync_count, cfg.max_frame_retries + 1);
+    }
 }
@@ -0,0 +1,124 @@
+//! Segment synchronisation using the Lepton GPIO3 VSYNC output.
+//!
+//! With GPIO3 in [`GpioMode::Vsync`](crate::oem::GpioMode::Vsync) the camera pulses the pin
+//! each time a new VoSPI segment (Lepton 3.x) or frame (Lepton 2.x) is ready. Waiting for
+//! that edge before reading avoids spinning on discard packets.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::InputPin;
+
+/// Interval between pin samples in [`PolledVsync`].
+const VSYNC_POLL_INTERVAL_US: u32 = 1;
+
+/// Waits for the VSYNC edge that marks the start of a segment.
+pub trait VsyncWait {
+    /// Blocks until the next VSYNC rising edge.
+    ///
+    /// Returns `false` if no edge arrived within `timeout_us`; capture then reads packets
+    /// without synchronisation, as if VSYNC were not in use.
+    fn wait_vsync(&mut self, delay: &mut impl DelayNs, timeout_us: u32) -> bool;
+}
+
+/// No VSYNC line: every wait returns immediately.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoVsync;
+
+impl VsyncWait for NoVsync {
+    fn wait_vsync(&mut self, _delay: &mut impl DelayNs, _timeout_us: u32) -> bool {
+        true
+    }
+}
+
+/// Busy-polls a GPIO input wired to the camera's GPIO3 for a low-to-high transition.
+///
+/// The pulse must be wider than the poll interval plus the pin read latency; use an
+/// interrupt-backed [`VsyncWait`] on hosts where that cannot be guaranteed.
+pub struct PolledVsync<P> {
+    pin: P,
+}
+
+impl<P: InputPin> PolledVsync<P> {
+    pub fn new(pin: P) -> Self {
+        PolledVsync { pin }
+    }
+
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: InputPin> VsyncWait for PolledVsync<P> {
+    fn wait_vsync(&mut self, delay: &mut impl DelayNs, timeout_us: u32) -> bool {
+        let mut seen_low = false;
+        let mut waited_us = 0u32;
+
+        while waited_us <= timeout_us {
+            // A failed read counts as neither level, so the edge must be seen cleanly.
+            match self.pin.is_high() {
+                Ok(true) if seen_low => return true,
+                Ok(high) => seen_low |= !high,
+                Err(_) => {}
+            }
+
+            delay.delay_us(VSYNC_POLL_INTERVAL_US);
+            waited_us += VSYNC_POLL_INTERVAL_US;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+    use embedded_hal::digital::ErrorType;
+
+    struct ScriptedPin {
+        levels: Vec<bool>,
+        idx: usize,
+    }
+
+    impl ErrorType for ScriptedPin {
+        type Error = Infallible;
+    }
+
+    impl InputPin for ScriptedPin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            let level = self.levels.get(self.idx).copied().unwrap_or(true);
+            self.idx += 1;
+            Ok(level)
+        }
+
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    #[test]
+    fn waits_for_rising_edge_not_high_level() {
+        let pin = ScriptedPin {
+            levels: vec![true, true, false, false, true],
+            idx: 0,
+        };
+        let mut vsync = PolledVsync::new(pin);
+        assert!(vsync.wait_vsync(&mut NoDelay, 100));
+        assert_eq!(vsync.release().idx, 5);
+    }
+
+    #[test]
+    fn stuck_high_line_times_out() {
+        let pin = ScriptedPin {
+            levels: Vec::new(),
+            idx: 0,
+        };
+        let mut vsync = PolledVsync::new(pin);
+        assert!(!vsync.wait_vsync(&mut NoDelay, 10));
+    }
+}